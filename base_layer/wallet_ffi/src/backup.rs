@@ -0,0 +1,236 @@
+// Copyright 2019. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Encrypted wallet backup/restore, exposed over FFI as `wallet_export_encrypted_backup` and
+//! `wallet_import_encrypted_backup` in `lib.rs`.
+//!
+//! The blob covers contacts and completed/pending transaction metadata, all reachable through this
+//! wallet's existing service handles. It is not a full device-to-device wallet move: restoring a
+//! backup replays contacts into a wallet created the normal way, with its own, separately-
+//! provisioned `NodeIdentity`, rather than recovering the original wallet's identity. The node
+//! identity itself is out of scope for this blob - `seed_words.rs` covers recovering that.
+//!
+//! Format: `[version: u8 = 1][salt: 16 bytes][nonce: 12 bytes][AES-256-GCM ciphertext]`, where the
+//! plaintext is a bincode-encoded `(Vec<Contact>, Vec<CompletedTransaction>, Vec<InboundTransaction>)`
+//! and the key is PBKDF2-HMAC-SHA256 over `passphrase` and `salt`, the same stretching `lock.rs` uses
+//! for its own encrypted blob.
+
+extern crate aes_gcm;
+extern crate hmac;
+extern crate lazy_static;
+extern crate pbkdf2;
+extern crate rand;
+extern crate sha2;
+
+use crate::{TariCompletedTransaction, TariContact, TariPendingInboundTransaction, TariWallet};
+use aes_gcm::{
+    aead::{Aead, NewAead},
+    Aes256Gcm,
+    Key,
+    Nonce,
+};
+use hmac::Hmac;
+use lazy_static::lazy_static;
+use libc::c_int;
+use pbkdf2::pbkdf2;
+use rand::RngCore;
+use sha2::Sha256;
+use std::{collections::HashMap, sync::Mutex};
+
+const FORMAT_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+/// Matches `lock.rs`'s `PBKDF2_ITERATIONS` so a backup blob and a lock blob cost the same to brute-force.
+const PBKDF2_ITERATIONS: u32 = 100_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupError {
+    SerializationFailed,
+    TruncatedBlob,
+    UnsupportedVersion,
+    WrongPassphraseOrCorrupt,
+}
+
+/// A distinct `error_out` code space for `wallet_export_encrypted_backup`/`_import_`, separate from
+/// the general `TariFfiError` codes used elsewhere in this crate, so a caller can tell "bad
+/// passphrase" apart from "version mismatch" apart from "truncated blob".
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TariBackupError {
+    Success = 0,
+    SerializationFailed = 1,
+    TruncatedBlob = 2,
+    UnsupportedVersion = 3,
+    WrongPassphraseOrCorrupt = 4,
+}
+
+impl TariBackupError {
+    pub fn code(self) -> c_int {
+        self as c_int
+    }
+}
+
+impl From<BackupError> for TariBackupError {
+    fn from(e: BackupError) -> Self {
+        match e {
+            BackupError::SerializationFailed => TariBackupError::SerializationFailed,
+            BackupError::TruncatedBlob => TariBackupError::TruncatedBlob,
+            BackupError::UnsupportedVersion => TariBackupError::UnsupportedVersion,
+            BackupError::WrongPassphraseOrCorrupt => TariBackupError::WrongPassphraseOrCorrupt,
+        }
+    }
+}
+
+pub type BackupPayload = (
+    Vec<TariContact>,
+    Vec<TariCompletedTransaction>,
+    Vec<TariPendingInboundTransaction>,
+);
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::<Hmac<Sha256>>(passphrase.as_bytes(), salt, PBKDF2_ITERATIONS, &mut key);
+    key
+}
+
+/// Gathers this wallet's contacts and transaction metadata and encrypts them with a key derived
+/// from `passphrase`.
+pub fn export(wallet: &TariWallet, passphrase: &str) -> Result<Vec<u8>, BackupError> {
+    let contacts = wallet
+        .runtime
+        .block_on(wallet.contacts_service.get_contacts())
+        .map_err(|_| BackupError::SerializationFailed)?;
+    let completed = wallet
+        .runtime
+        .block_on(wallet.transaction_service.get_completed_transactions())
+        .map(|m| m.values().cloned().collect())
+        .unwrap_or_else(|_| Vec::new());
+    let pending_inbound = wallet
+        .runtime
+        .block_on(wallet.transaction_service.get_pending_inbound_transactions())
+        .map(|m| m.values().cloned().collect())
+        .unwrap_or_else(|_| Vec::new());
+
+    let payload: BackupPayload = (contacts, completed, pending_inbound);
+    let plaintext = bincode::serialize(&payload).map_err(|_| BackupError::SerializationFailed)?;
+
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    let mut rng = rand::thread_rng();
+    rng.fill_bytes(&mut salt);
+    rng.fill_bytes(&mut nonce_bytes);
+
+    let key = derive_key(passphrase, &salt);
+    let cipher = Aes256Gcm::new(Key::from_slice(&key));
+    let nonce = Nonce::from_slice(&nonce_bytes);
+    let ciphertext = cipher
+        .encrypt(nonce, plaintext.as_ref())
+        .map_err(|_| BackupError::SerializationFailed)?;
+
+    let mut blob = Vec::with_capacity(1 + SALT_LEN + NONCE_LEN + ciphertext.len());
+    blob.push(FORMAT_VERSION);
+    blob.extend_from_slice(&salt);
+    blob.extend_from_slice(&nonce_bytes);
+    blob.extend_from_slice(&ciphertext);
+    Ok(blob)
+}
+
+/// Decrypts a blob produced by `export` and returns the contacts and transaction metadata it
+/// contains. `lib.rs` replays the contacts into a freshly-created wallet via
+/// `contacts_service.save_contact`, the same call `wallet_add_contact` makes; the transaction
+/// metadata has no such write path on
+/// `transaction_service` (there's no way to replay a historical transaction into it), so it's handed
+/// back as plain `TariCompletedTransactions`/`TariPendingInboundTransactions` collections instead,
+/// for a caller to inspect or archive rather than it being silently discarded.
+pub fn import(bytes: &[u8], passphrase: &str) -> Result<BackupPayload, BackupError> {
+    if bytes.is_empty() || bytes[0] != FORMAT_VERSION {
+        if bytes.is_empty() {
+            return Err(BackupError::TruncatedBlob);
+        }
+        return Err(BackupError::UnsupportedVersion);
+    }
+    if bytes.len() < 1 + SALT_LEN + NONCE_LEN {
+        return Err(BackupError::TruncatedBlob);
+    }
+
+    let salt = &bytes[1..1 + SALT_LEN];
+    let nonce_bytes = &bytes[1 + SALT_LEN..1 + SALT_LEN + NONCE_LEN];
+    let ciphertext = &bytes[1 + SALT_LEN + NONCE_LEN..];
+
+    let key = derive_key(passphrase, salt);
+    let cipher = Aes256Gcm::new(Key::from_slice(&key));
+    let nonce = Nonce::from_slice(nonce_bytes);
+    let plaintext = cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|_| BackupError::WrongPassphraseOrCorrupt)?;
+
+    bincode::deserialize(&plaintext).map_err(|_| BackupError::WrongPassphraseOrCorrupt)
+}
+
+lazy_static! {
+    /// Transaction metadata handed back by the most recent `import` call against each restored
+    /// wallet, keyed by that wallet's untagged pointer address - the same keying `coin_selection.rs`'s
+    /// `DEFAULT_STRATEGY` uses. `import` itself can't stash this (it doesn't know the new wallet's
+    /// address yet; `wallet_create` runs after it), so `wallet_import_encrypted_backup` in `lib.rs`
+    /// calls `stash` once the restored wallet exists, and
+    /// `wallet_import_encrypted_backup_completed_transactions`/`_pending_inbound_transactions` read it
+    /// back out.
+    static ref IMPORTED: Mutex<HashMap<usize, (Vec<TariCompletedTransaction>, Vec<TariPendingInboundTransaction>)>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Records the transaction metadata from a backup import against the wallet it was restored into.
+pub fn stash(
+    wallet_key: usize,
+    completed: Vec<TariCompletedTransaction>,
+    pending_inbound: Vec<TariPendingInboundTransaction>,
+) {
+    IMPORTED.lock().unwrap().insert(wallet_key, (completed, pending_inbound));
+}
+
+/// Returns the completed transactions from `wallet_key`'s most recent backup import, or an empty
+/// `Vec` if it was never restored from a backup.
+pub fn completed_transactions(wallet_key: usize) -> Vec<TariCompletedTransaction> {
+    IMPORTED
+        .lock()
+        .unwrap()
+        .get(&wallet_key)
+        .map(|(completed, _)| completed.clone())
+        .unwrap_or_default()
+}
+
+/// Returns the pending inbound transactions from `wallet_key`'s most recent backup import, or an
+/// empty `Vec` if it was never restored from a backup.
+pub fn pending_inbound_transactions(wallet_key: usize) -> Vec<TariPendingInboundTransaction> {
+    IMPORTED
+        .lock()
+        .unwrap()
+        .get(&wallet_key)
+        .map(|(_, pending_inbound)| pending_inbound.clone())
+        .unwrap_or_default()
+}
+
+/// Forgets any backup-import transaction metadata stashed for `wallet_key`. Called from
+/// `wallet_destroy`.
+pub fn clear(wallet_key: usize) {
+    IMPORTED.lock().unwrap().remove(&wallet_key);
+}
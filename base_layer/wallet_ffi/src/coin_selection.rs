@@ -0,0 +1,232 @@
+// Copyright 2019. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Pluggable coin-selection strategies, selectable per-call from FFI via
+//! `wallet_send_transaction_with_strategy`, previewable via `wallet_estimate_fee`, or set as a
+//! wallet's sticky default via `wallet_set_coin_selection_strategy` so callers that always send the
+//! same way don't have to pass a strategy on every send.
+//!
+//! TODO: `output_manager_service` doesn't expose a UTXO-listing method in this tree yet, only
+//! `get_balance()`, so there is nothing for these strategies to select over besides the wallet's
+//! total balance as a single pseudo-UTXO (see the callers in `lib.rs`). Once a listing method
+//! exists, pass its output straight into `select` unchanged - the algorithms here don't assume
+//! anything about where the candidates came from.
+
+extern crate lazy_static;
+
+use lazy_static::lazy_static;
+use std::{collections::HashMap, sync::Mutex};
+
+lazy_static! {
+    /// Per-wallet default set by `wallet_set_coin_selection_strategy`, keyed by the wallet's
+    /// untagged pointer address (the same keying scheme `lock.rs`/`filelock.rs` use).
+    static ref DEFAULT_STRATEGY: Mutex<HashMap<usize, TariCoinSelectionStrategy>> = Mutex::new(HashMap::new());
+}
+
+/// Sets `wallet_key`'s sticky default coin-selection strategy.
+pub fn set_default(wallet_key: usize, strategy: TariCoinSelectionStrategy) {
+    DEFAULT_STRATEGY.lock().unwrap().insert(wallet_key, strategy);
+}
+
+/// `wallet_key`'s sticky default strategy, or `LargestFirst` if `set_default` was never called for
+/// it.
+pub fn default_for(wallet_key: usize) -> TariCoinSelectionStrategy {
+    DEFAULT_STRATEGY
+        .lock()
+        .unwrap()
+        .get(&wallet_key)
+        .copied()
+        .unwrap_or(TariCoinSelectionStrategy::LargestFirst)
+}
+
+/// Forgets `wallet_key`'s sticky default strategy, if any. Called from `wallet_destroy`.
+pub fn clear_default(wallet_key: usize) {
+    DEFAULT_STRATEGY.lock().unwrap().remove(&wallet_key);
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TariCoinSelectionStrategy {
+    LargestFirst = 0,
+    OldestFirst = 1,
+    BranchAndBound = 2,
+}
+
+/// A candidate input for coin selection. `timestamp` is whatever ordering key "oldest" should mean
+/// for the backing UTXO store (e.g. block height or receipt time).
+#[derive(Debug, Clone, Copy)]
+pub struct Utxo {
+    pub id: u64,
+    pub value: u64,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Selection {
+    pub selected: Vec<Utxo>,
+    pub total_value: u64,
+    pub needs_change: bool,
+}
+
+/// Upper bound on branch-and-bound search nodes before giving up and falling back to accumulative
+/// largest-first selection.
+const BNB_MAX_TRIES: usize = 100_000;
+
+/// Selects UTXOs from `utxos` to cover `target` (amount + fee) using `strategy`. `cost_of_change`
+/// is the extra fee a change output would add; branch-and-bound uses it to prefer a changeless
+/// match when one exists within `[target, target + cost_of_change]`.
+pub fn select(strategy: TariCoinSelectionStrategy, utxos: &[Utxo], target: u64, cost_of_change: u64) -> Option<Selection> {
+    match strategy {
+        TariCoinSelectionStrategy::LargestFirst => select_largest_first(utxos, target),
+        TariCoinSelectionStrategy::OldestFirst => select_oldest_first(utxos, target),
+        TariCoinSelectionStrategy::BranchAndBound => {
+            select_branch_and_bound(utxos, target, cost_of_change).or_else(|| select_largest_first(utxos, target))
+        },
+    }
+}
+
+fn select_largest_first(utxos: &[Utxo], target: u64) -> Option<Selection> {
+    let mut sorted: Vec<Utxo> = utxos.to_vec();
+    sorted.sort_by(|a, b| b.value.cmp(&a.value));
+    accumulate(sorted, target)
+}
+
+fn select_oldest_first(utxos: &[Utxo], target: u64) -> Option<Selection> {
+    let mut sorted: Vec<Utxo> = utxos.to_vec();
+    sorted.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    accumulate(sorted, target)
+}
+
+fn accumulate(sorted: Vec<Utxo>, target: u64) -> Option<Selection> {
+    let mut selected = Vec::new();
+    let mut total = 0u64;
+    for utxo in sorted {
+        if total >= target {
+            break;
+        }
+        total += utxo.value;
+        selected.push(utxo);
+    }
+    if total < target {
+        return None;
+    }
+    Some(Selection {
+        needs_change: total > target,
+        total_value: total,
+        selected,
+    })
+}
+
+/// Depth-first search over include/exclude decisions on UTXOs sorted descending by value, pruning a
+/// branch once its running total exceeds `target + cost_of_change` (the upper bound past which a
+/// changeless match is no longer possible) or once the value still available can't reach `target`
+/// even if every remaining UTXO were included. Returns the first exact-enough match found within
+/// `[target, target + cost_of_change]`, or `None` if the search space is exhausted (or the try
+/// budget runs out) without one.
+fn select_branch_and_bound(utxos: &[Utxo], target: u64, cost_of_change: u64) -> Option<Selection> {
+    let mut sorted: Vec<Utxo> = utxos.to_vec();
+    sorted.sort_by(|a, b| b.value.cmp(&a.value));
+    let upper_bound = target + cost_of_change;
+    let total_value: u64 = sorted.iter().map(|u| u.value).sum();
+
+    let mut tries = 0usize;
+    let mut best: Option<Vec<usize>> = None;
+    let mut path = Vec::new();
+    recurse(
+        &sorted,
+        0,
+        &mut path,
+        0,
+        total_value,
+        target,
+        upper_bound,
+        &mut tries,
+        &mut best,
+    );
+
+    best.map(|indices| {
+        let chosen: Vec<Utxo> = indices.iter().map(|&i| sorted[i]).collect();
+        let total: u64 = chosen.iter().map(|u| u.value).sum();
+        Selection {
+            needs_change: total > target,
+            total_value: total,
+            selected: chosen,
+        }
+    })
+}
+
+/// Returns `true` once the search should stop (either a match was found or the try budget ran out),
+/// `false` to keep backtracking.
+#[allow(clippy::too_many_arguments)]
+fn recurse(
+    sorted: &[Utxo],
+    index: usize,
+    path: &mut Vec<usize>,
+    selected_value: u64,
+    remaining_value: u64,
+    target: u64,
+    upper_bound: u64,
+    tries: &mut usize,
+    best: &mut Option<Vec<usize>>,
+) -> bool {
+    *tries += 1;
+    if *tries > BNB_MAX_TRIES {
+        return true;
+    }
+    if selected_value >= target && selected_value <= upper_bound {
+        *best = Some(path.clone());
+        return true;
+    }
+    if selected_value > upper_bound || selected_value + remaining_value < target || index == sorted.len() {
+        return false;
+    }
+
+    let next_remaining = remaining_value - sorted[index].value;
+
+    path.push(index);
+    if recurse(
+        sorted,
+        index + 1,
+        path,
+        selected_value + sorted[index].value,
+        next_remaining,
+        target,
+        upper_bound,
+        tries,
+        best,
+    ) {
+        return true;
+    }
+    path.pop();
+
+    recurse(
+        sorted,
+        index + 1,
+        path,
+        selected_value,
+        next_remaining,
+        target,
+        upper_bound,
+        tries,
+        best,
+    )
+}
@@ -0,0 +1,121 @@
+// Copyright 2019. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Event delivery to Dart/Flutter via `allo-isolate` port posting, as an alternative to the raw
+//! `unsafe extern "C" fn` callbacks in the `Callbacks` section of `lib.rs`. Those callbacks are
+//! invoked synchronously on the wallet's runtime thread, which is unsafe to call back into a Dart
+//! isolate from; posting a message to a `SendPort` instead lets the Dart VM schedule delivery on
+//! its own thread.
+//!
+//! A client first calls `wallet_set_dart_post_cobject` once per process with the function pointer
+//! Dart's `NativeApi.postCObject` resolves to, then `wallet_register_event_port` per wallet with the
+//! `SendPort.nativePort` to receive `[event_type, tx_id, amount]` messages on. Only the events this
+//! crate can already observe (received transaction, received transaction reply) are forwarded; no
+//! broadcast/mined/confirmed events are forwarded, since the transaction service here emits none
+//! (see the note after the callback registration functions in `lib.rs`).
+
+extern crate allo_isolate;
+
+use crate::{
+    completed_transaction_destroy,
+    completed_transaction_get_amount,
+    completed_transaction_get_transaction_id,
+    pending_inbound_transaction_destroy,
+    pending_inbound_transaction_get_amount,
+    pending_inbound_transaction_get_transaction_id,
+    wallet_call_back_register_received_transaction,
+    wallet_call_back_register_received_transaction_reply,
+    TariCompletedTransaction,
+    TariPendingInboundTransaction,
+    TariWallet,
+};
+use allo_isolate::{ffi::DartPostCObjectFnType, IntoDart, Isolate};
+use std::sync::atomic::{AtomicI64, Ordering};
+
+#[repr(i64)]
+enum DartEventType {
+    TransactionReceived = 0,
+    TransactionReceivedReply = 1,
+}
+
+/// The `SendPort.nativePort` registered by `wallet_register_event_port`, or `-1` if none has been
+/// registered yet. A single process-wide port mirrors the existing callback registration functions,
+/// which likewise have no way to address more than one wallet.
+static EVENT_PORT: AtomicI64 = AtomicI64::new(-1);
+
+/// Stores the `NativeApi.postCObject` function pointer Dart resolves, so this crate can later post
+/// messages to a `SendPort` without the Dart VM ever calling into this crate from an unexpected
+/// thread. Must be called once before `wallet_register_event_port`.
+///
+/// ## Arguments
+/// `post_cobject` - The Dart VM's native `postCObject` function pointer
+///
+/// ## Returns
+/// `()` - Does not return a value, equivalent to void in C
+#[no_mangle]
+pub unsafe extern "C" fn wallet_set_dart_post_cobject(post_cobject: DartPostCObjectFnType) {
+    allo_isolate::store_dart_post_cobject(post_cobject);
+}
+
+/// Registers a Dart `SendPort` to receive wallet events, forwarding them in place of (not in
+/// addition to) the raw C callbacks registered via `wallet_call_back_register_received_transaction`
+/// and `wallet_call_back_register_received_transaction_reply`.
+///
+/// ## Arguments
+/// `wallet` - The pointer to a TariWallet
+/// `send_port` - The `SendPort.nativePort` to post `[event_type, tx_id, amount]` messages to
+///
+/// ## Returns
+/// `bool` - Returns true if both underlying callback registrations succeeded
+#[no_mangle]
+pub unsafe extern "C" fn wallet_register_event_port(wallet: *mut TariWallet, send_port: i64) -> bool {
+    EVENT_PORT.store(send_port, Ordering::SeqCst);
+    wallet_call_back_register_received_transaction(wallet, received_transaction_trampoline) &&
+        wallet_call_back_register_received_transaction_reply(wallet, received_transaction_reply_trampoline)
+}
+
+unsafe extern "C" fn received_transaction_trampoline(tx: *mut TariPendingInboundTransaction) {
+    let tx_id = pending_inbound_transaction_get_transaction_id(tx);
+    let amount = pending_inbound_transaction_get_amount(tx);
+    post_event(DartEventType::TransactionReceived, tx_id, amount);
+    pending_inbound_transaction_destroy(tx);
+}
+
+unsafe extern "C" fn received_transaction_reply_trampoline(tx: *mut TariCompletedTransaction) {
+    let tx_id = completed_transaction_get_transaction_id(tx);
+    let amount = completed_transaction_get_amount(tx);
+    post_event(
+        DartEventType::TransactionReceivedReply,
+        if tx_id.is_some { tx_id.value as u64 } else { 0 },
+        if amount.is_some { amount.value } else { 0 },
+    );
+    completed_transaction_destroy(tx);
+}
+
+fn post_event(event_type: DartEventType, tx_id: u64, amount: u64) {
+    let port = EVENT_PORT.load(Ordering::SeqCst);
+    if port < 0 {
+        return;
+    }
+    let message = vec![event_type as i64, tx_id as i64, amount as i64];
+    Isolate::new(port).post(message.into_dart());
+}
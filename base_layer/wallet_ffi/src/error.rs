@@ -20,6 +20,8 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use libc::c_int;
+use std::cell::RefCell;
 use tari_comms::connection::net_address::NetAddressError;
 use tari_utilities::{hex::HexError, ByteArrayError};
 use tari_wallet::{
@@ -28,12 +30,158 @@ use tari_wallet::{
     transaction_service::error::{TransactionServiceError, TransactionStorageError},
 };
 
+/// The set of outcomes an FFI call can signal through its trailing `error_out` parameter. `Success`
+/// means the returned pointer/value is valid; any other variant means it must be treated as absent
+/// rather than inspected, since a `null`/`0` return is also used to mean "empty" in several places.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TariFfiError {
+    Success = 0,
+    NullArgument = 1,
+    InvalidHex = 2,
+    DeserializationFailed = 3,
+    IndexOutOfBounds = 4,
+    InvalidLength = 5,
+    /// A persistent-database backend was requested that this build doesn't have, rather than the
+    /// passphrase being wrong or the database being corrupt - this tree only has
+    /// `WalletMemoryDatabase` to offer. Reserved for the day a persistent backend (e.g. SQLite) is
+    /// added to the dependency tree; no FFI function in this crate currently returns it.
+    PersistenceUnavailable = 6,
+}
+
+impl TariFfiError {
+    pub fn code(self) -> c_int {
+        self as c_int
+    }
+}
+
+/// Writes `err` into `error_out` if `error_out` is not null. This is the single place that every
+/// fallible FFI function should go through so the out-parameter convention stays consistent.
+///
+/// # Safety
+/// `error_out` must either be null or point to valid, writable `c_int` storage.
+pub unsafe fn set_error(error_out: *mut c_int, err: TariFfiError) {
+    if !error_out.is_null() {
+        *error_out = err.code();
+    }
+}
+
+/// A small, stable grouping of `LibWalletError` codes, so an FFI client can branch on a handful of known categories
+/// instead of embedding a copy of the full code table in this file. Mirrors the section comments already used to
+/// organize the code ranges below (Output Manager/Transaction Service/Comms Stack/Hex+ByteArray encoding).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LibWalletErrorCategory {
+    Funds = 0,
+    TransactionProtocol = 1,
+    Storage = 2,
+    Network = 3,
+    Encoding = 4,
+    Unknown = 5,
+}
+
+/// The stable symbolic name, category, and retryability for `code`, used by both
+/// `liberror_name`/`liberror_category`/`liberror_is_transient` and the `LibWalletError` constructors below, so all
+/// three stay in lock-step with the code assigned at each match arm below. Falls back to `("Unknown", Unknown,
+/// false)` for a code this crate doesn't recognise, mirroring the `999` catch-all - an unrecognised code is treated
+/// as non-transient so a client doesn't busy-retry something it can't identify.
+///
+/// `is_transient` is true only for codes where simply waiting and retrying the same call can plausibly succeed
+/// (comms/connectivity and the data-directory lock, which another process may release) - false for validation
+/// errors like `DuplicateOutput`/`OutputAlreadySpent` that need different input, not another attempt.
+fn describe_code(code: i32) -> (&'static str, LibWalletErrorCategory, bool) {
+    use LibWalletErrorCategory::*;
+    match code {
+        101 => ("NotEnoughFunds", Funds, false),
+        102 => ("IncompleteTransaction", TransactionProtocol, false),
+        103 => ("DuplicateOutput", Storage, false),
+        104 => ("ValuesNotFound", Storage, false),
+        105 => ("OutputAlreadySpent", Storage, false),
+        106 => ("PendingTransactionNotFound", Storage, false),
+        107 => ("OutputManagerStorageDuplicateOutput", Storage, false),
+        108 => ("OutputValueNotFound", Storage, false),
+        200 => ("InvalidStateError", TransactionProtocol, false),
+        201 => ("TransactionProtocolError", TransactionProtocol, false),
+        202 => ("RepeatedMessageError", TransactionProtocol, false),
+        203 => ("TransactionDoesNotExistError", Storage, false),
+        204 => ("TransactionNotEnoughFunds", Funds, false),
+        205 => ("TransactionOutputManagerError", Funds, false),
+        206 => ("TransactionError", TransactionProtocol, false),
+        207 => ("TransactionStorageDuplicateOutput", Storage, false),
+        208 => ("TransactionStorageValueNotFound", Storage, false),
+        300 => ("NetAddressParseFailed", Network, false),
+        301 => ("WalletDataDirectoryLocked", Storage, true),
+        400 => ("HexLengthError", Encoding, false),
+        401 => ("HexConversionError", Encoding, false),
+        402 => ("HexInvalidCharacter", Encoding, false),
+        403 => ("ByteArrayIncorrectLength", Encoding, false),
+        404 => ("ByteArrayConversionError", Encoding, false),
+        _ => ("Unknown", Unknown, false),
+    }
+}
+
+/// `code`'s stable symbolic name, e.g. `"NotEnoughFunds"` for `101`. See `describe_code`.
+pub fn name_for_code(code: i32) -> &'static str {
+    describe_code(code).0
+}
+
+/// `code`'s category. See `describe_code`.
+pub fn category_for_code(code: i32) -> LibWalletErrorCategory {
+    describe_code(code).1
+}
+
+/// Whether `code` is worth auto-retrying without changing anything about the call that produced it. See
+/// `describe_code`.
+pub fn is_transient_for_code(code: i32) -> bool {
+    describe_code(code).2
+}
+
 /// This struct is meant to hold an error for use by FFI client applications. The error has an integer code and string
-/// message
+/// message, a stable symbolic name/category/retryability derived from that code (see `describe_code`), a
+/// `source_chain` of the nested error variants that produced it (outermost first, see `with_chain`), and an optional
+/// fund-shortfall detail (`available`/`required`/`fee_estimate`) for a `NotEnoughFunds` code (101/204). Neither
+/// `OutputManagerError::NotEnoughFunds` nor `TransactionServiceError::OutputManagerError(NotEnoughFunds)` carries
+/// those numbers itself, so they can't be filled in by the `From<WalletError>` conversion below; instead,
+/// `set_last_error_funds_detail` patches them onto the last recorded error from the one call site
+/// (`wallet_send_transaction` in `lib.rs`) that has the wallet's current balance and the requested amount/fee
+/// on hand at the moment the error is raised.
 #[derive(Debug, Clone)]
 pub struct LibWalletError {
     pub code: i32,
     pub message: String,
+    pub name: &'static str,
+    pub category: LibWalletErrorCategory,
+    pub is_transient: bool,
+    pub source_chain: Vec<String>,
+    pub available: Option<u64>,
+    pub required: Option<u64>,
+    pub fee_estimate: Option<u64>,
+}
+
+impl LibWalletError {
+    /// A `LibWalletError` whose `source_chain` is just `message` itself, for a source error with no nested variants
+    /// to walk (e.g. `HexError`, `ByteArrayError`).
+    fn new(code: i32, message: String) -> Self {
+        Self::with_chain(code, message.clone(), vec![message])
+    }
+
+    /// As `new`, but with an explicit `source_chain` - one entry per nested error variant peeled on the way down to
+    /// the root cause, outermost (`message` itself) first. See the `WalletError` nested matches below for how it's
+    /// built.
+    fn with_chain(code: i32, message: String, source_chain: Vec<String>) -> Self {
+        let (name, category, is_transient) = describe_code(code);
+        Self {
+            code,
+            message,
+            name,
+            category,
+            is_transient,
+            source_chain,
+            available: None,
+            required: None,
+            fee_estimate: None,
+        }
+    }
 }
 
 /// This implementation maps the internal WalletError to a set of LibWalletErrors. The mapping is explicitly manager
@@ -42,103 +190,100 @@ impl From<WalletError> for LibWalletError {
     fn from(w: WalletError) -> Self {
         match w {
             // Output Manager Service Errors
-            WalletError::OutputManagerError(OutputManagerError::NotEnoughFunds) => Self {
-                code: 101,
-                message: format!("{:?}", w),
-            },
-            WalletError::OutputManagerError(OutputManagerError::IncompleteTransaction) => Self {
-                code: 102,
-                message: format!("{:?}", w),
-            },
-            WalletError::OutputManagerError(OutputManagerError::DuplicateOutput) => Self {
-                code: 103,
-                message: format!("{:?}", w),
-            },
-            WalletError::OutputManagerError(OutputManagerError::OutputManagerStorageError(
-                OutputManagerStorageError::ValuesNotFound,
-            )) => Self {
-                code: 104,
-                message: format!("{:?}", w),
-            },
-            WalletError::OutputManagerError(OutputManagerError::OutputManagerStorageError(
-                OutputManagerStorageError::OutputAlreadySpent,
-            )) => Self {
-                code: 105,
-                message: format!("{:?}", w),
-            },
-            WalletError::OutputManagerError(OutputManagerError::OutputManagerStorageError(
-                OutputManagerStorageError::PendingTransactionNotFound,
-            )) => Self {
-                code: 106,
-                message: format!("{:?}", w),
-            },
-            WalletError::OutputManagerError(OutputManagerError::OutputManagerStorageError(
-                OutputManagerStorageError::DuplicateOutput,
-            )) => Self {
-                code: 107,
-                message: format!("{:?}", w),
-            },
-            WalletError::OutputManagerError(OutputManagerError::OutputManagerStorageError(
-                OutputManagerStorageError::ValueNotFound(_),
-            )) => Self {
-                code: 108,
-                message: format!("{:?}", w),
+            //
+            // `OutputManagerError::NotEnoughFunds` is a unit variant in this tree - it carries no available/
+            // required/fee numbers to forward - so this arm leaves `available`/`required`/`fee_estimate` unset;
+            // `set_last_error_funds_detail` patches them onto the recorded error afterwards (see the struct doc).
+            WalletError::OutputManagerError(ref inner) => {
+                let chain = vec![format!("{:?}", w), format!("{:?}", inner)];
+                match inner {
+                    OutputManagerError::NotEnoughFunds => Self::with_chain(101, format!("{:?}", w), chain),
+                    OutputManagerError::IncompleteTransaction => Self::with_chain(102, format!("{:?}", w), chain),
+                    OutputManagerError::DuplicateOutput => Self::with_chain(103, format!("{:?}", w), chain),
+                    OutputManagerError::OutputManagerStorageError(ref storage) => {
+                        let mut chain = chain;
+                        chain.push(format!("{:?}", storage));
+                        match storage {
+                            OutputManagerStorageError::ValuesNotFound => {
+                                Self::with_chain(104, format!("{:?}", w), chain)
+                            },
+                            OutputManagerStorageError::OutputAlreadySpent => {
+                                Self::with_chain(105, format!("{:?}", w), chain)
+                            },
+                            OutputManagerStorageError::PendingTransactionNotFound => {
+                                Self::with_chain(106, format!("{:?}", w), chain)
+                            },
+                            OutputManagerStorageError::DuplicateOutput => {
+                                Self::with_chain(107, format!("{:?}", w), chain)
+                            },
+                            OutputManagerStorageError::ValueNotFound(_) => {
+                                Self::with_chain(108, format!("{:?}", w), chain)
+                            },
+                            _ => Self::with_chain(999, format!("{:?}", w), chain),
+                        }
+                    },
+                    _ => Self::with_chain(999, format!("{:?}", w), chain),
+                }
             },
             // Transaction Service Errors
-            WalletError::TransactionServiceError(TransactionServiceError::InvalidStateError) => Self {
-                code: 200,
-                message: format!("{:?}", w),
-            },
-            WalletError::TransactionServiceError(TransactionServiceError::TransactionProtocolError(_)) => Self {
-                code: 201,
-                message: format!("{:?}", w),
-            },
-            WalletError::TransactionServiceError(TransactionServiceError::RepeatedMessageError) => Self {
-                code: 202,
-                message: format!("{:?}", w),
-            },
-            WalletError::TransactionServiceError(TransactionServiceError::TransactionDoesNotExistError) => Self {
-                code: 203,
-                message: format!("{:?}", w),
-            },
-            WalletError::TransactionServiceError(TransactionServiceError::OutputManagerError(
-                OutputManagerError::NotEnoughFunds,
-            )) => Self {
-                code: 204,
-                message: format!("{:?}", w),
-            },
-            WalletError::TransactionServiceError(TransactionServiceError::OutputManagerError(_)) => Self {
-                code: 205,
-                message: format!("{:?}", w),
-            },
-            WalletError::TransactionServiceError(TransactionServiceError::TransactionError(_)) => Self {
-                code: 206,
-                message: format!("{:?}", w),
-            },
-            WalletError::TransactionServiceError(TransactionServiceError::TransactionStorageError(
-                TransactionStorageError::DuplicateOutput,
-            )) => Self {
-                code: 207,
-                message: format!("{:?}", w),
-            },
-
-            WalletError::TransactionServiceError(TransactionServiceError::TransactionStorageError(
-                TransactionStorageError::ValueNotFound(_),
-            )) => Self {
-                code: 208,
-                message: format!("{:?}", w),
+            WalletError::TransactionServiceError(ref inner) => {
+                let chain = vec![format!("{:?}", w), format!("{:?}", inner)];
+                match inner {
+                    TransactionServiceError::InvalidStateError => Self::with_chain(200, format!("{:?}", w), chain),
+                    TransactionServiceError::TransactionProtocolError(_) => {
+                        Self::with_chain(201, format!("{:?}", w), chain)
+                    },
+                    TransactionServiceError::RepeatedMessageError => Self::with_chain(202, format!("{:?}", w), chain),
+                    TransactionServiceError::TransactionDoesNotExistError => {
+                        Self::with_chain(203, format!("{:?}", w), chain)
+                    },
+                    // See the comment on the 101 arm above - no balance numbers are available to attach here either.
+                    TransactionServiceError::OutputManagerError(ref om) => {
+                        let mut chain = chain;
+                        chain.push(format!("{:?}", om));
+                        match om {
+                            OutputManagerError::NotEnoughFunds => Self::with_chain(204, format!("{:?}", w), chain),
+                            _ => Self::with_chain(205, format!("{:?}", w), chain),
+                        }
+                    },
+                    TransactionServiceError::TransactionError(_) => Self::with_chain(206, format!("{:?}", w), chain),
+                    TransactionServiceError::TransactionStorageError(ref ts) => {
+                        let mut chain = chain;
+                        chain.push(format!("{:?}", ts));
+                        match ts {
+                            TransactionStorageError::DuplicateOutput => {
+                                Self::with_chain(207, format!("{:?}", w), chain)
+                            },
+                            TransactionStorageError::ValueNotFound(_) => {
+                                Self::with_chain(208, format!("{:?}", w), chain)
+                            },
+                            _ => Self::with_chain(999, format!("{:?}", w), chain),
+                        }
+                    },
+                    _ => Self::with_chain(999, format!("{:?}", w), chain),
+                }
             },
             // Comms Stack errors
-            WalletError::NetAddressError(NetAddressError::ParseFailed) => Self {
-                code: 300,
-                message: format!("{:?}", w),
+            WalletError::NetAddressError(ref inner) => {
+                let chain = vec![format!("{:?}", w), format!("{:?}", inner)];
+                match inner {
+                    NetAddressError::ParseFailed => Self::with_chain(300, format!("{:?}", w), chain),
+                    _ => Self::with_chain(999, format!("{:?}", w), chain),
+                }
             },
 
-            // This is the catch all error code. Any error that is not explicitly mapped above will be given this code
-            _ => Self {
-                code: 999,
-                message: format!("{:?}", w).to_string(),
-            },
+            // This is the catch all error code. Any error that is not explicitly mapped above will be given this
+            // code. `tari_comms`'s connection-layer errors (connection-refused, dial-timeout, peer-not-found,
+            // message-send-failure) fall through to here rather than a dedicated 302-305 block: this tree doesn't
+            // vendor those error types (`ConnectionError`/`DialError`/`PeerConnectionError` and friends), so there's
+            // no real enum variant to destructure by name the way every other arm above does. An earlier version of
+            // this match both declared those codes in `describe_code` and classified by substring-matching the
+            // `Debug` text instead of a real variant - the former shipped a code table claiming coverage no `From`
+            // arm ever produced, and the latter silently misclassified anything whose Debug output happened to
+            // contain the same words. Both are worse than just falling through to 999 until those types are
+            // vendored; once they are, add a `WalletError::CommsError(ref inner)` (or whatever the real variant is
+            // named) arm above that destructures them into a new 302+ block, with matching `describe_code` entries.
+            _ => Self::new(999, format!("{:?}", w).to_string()),
         }
     }
 }
@@ -148,24 +293,12 @@ impl From<WalletError> for LibWalletError {
 impl From<HexError> for LibWalletError {
     fn from(h: HexError) -> Self {
         match h {
-            HexError::LengthError => Self {
-                code: 400,
-                message: format!("{:?}", h).to_string(),
-            },
-            HexError::HexConversionError => Self {
-                code: 401,
-                message: format!("{:?}", h).to_string(),
-            },
-            HexError::InvalidCharacter(_) => Self {
-                code: 402,
-                message: format!("{:?}", h).to_string(),
-            },
+            HexError::LengthError => Self::new(400, format!("{:?}", h)),
+            HexError::HexConversionError => Self::new(401, format!("{:?}", h).to_string()),
+            HexError::InvalidCharacter(_) => Self::new(402, format!("{:?}", h).to_string()),
 
             // This is the catch all error code. Any error that is not explicitly mapped above will be given this code
-            _ => Self {
-                code: 999,
-                message: format!("{:?}", h).to_string(),
-            },
+            _ => Self::new(999, format!("{:?}", h).to_string()),
         }
     }
 }
@@ -175,19 +308,122 @@ impl From<HexError> for LibWalletError {
 impl From<ByteArrayError> for LibWalletError {
     fn from(b: ByteArrayError) -> Self {
         match b {
-            ByteArrayError::IncorrectLength => Self {
-                code: 403,
-                message: format!("{:?}", b).to_string(),
-            },
-            ByteArrayError::ConversionError(_) => Self {
-                code: 404,
-                message: format!("{:?}", b).to_string(),
-            },
+            ByteArrayError::IncorrectLength => Self::new(403, format!("{:?}", b).to_string()),
+            ByteArrayError::ConversionError(_) => Self::new(404, format!("{:?}", b).to_string()),
             // This is the catch all error code. Any error that is not explicitly mapped above will be given this code
-            _ => Self {
-                code: 999,
-                message: format!("{:?}", b).to_string(),
+            _ => Self::new(999, format!("{:?}", b).to_string()),
+        }
+    }
+}
+
+/// `comms_config_create` parses the supplied address before a `WalletError` ever exists, so it needs its own mapping
+/// straight from `NetAddressError`. The code matches the `WalletError::NetAddressError` arm above so a client sees
+/// the same code regardless of which layer the parse failure surfaced at.
+impl From<NetAddressError> for LibWalletError {
+    fn from(n: NetAddressError) -> Self {
+        match n {
+            NetAddressError::ParseFailed => Self::new(300, format!("{:?}", n).to_string()),
+            _ => Self::new(999, format!("{:?}", n).to_string()),
+        }
+    }
+}
+
+/// `wallet_create` checks for a contested data-directory lock before a `WalletError` ever exists, so it needs its
+/// own mapping straight from `crate::filelock::LockError`. `301` shares the `3xx` numbering `NetAddressError` above
+/// started, but categorizes as `Storage` (see `describe_code`), not `Network`: a contended data-directory file
+/// lock is a storage condition, and a client branching on category should see "another wallet instance has this
+/// open", not "check your connection". It keeps `is_transient = true` since the other process may release the
+/// lock and a retry can then succeed.
+impl From<crate::filelock::LockError> for LibWalletError {
+    fn from(e: crate::filelock::LockError) -> Self {
+        match e {
+            crate::filelock::LockError::AlreadyLocked => {
+                Self::new(301, "wallet data directory is already locked by another wallet instance".to_string())
             },
+            crate::filelock::LockError::Io(io_err) => Self::new(999, format!("{:?}", io_err)),
         }
     }
 }
+
+thread_local! {
+    /// The most recent `LibWalletError` set by `set_last_error` on this thread. Since the wallet's
+    /// async services all run on a single `tari_wallet`-owned runtime and every FFI call blocks on
+    /// that runtime before returning, the calling thread's last error always corresponds to the FFI
+    /// call that just returned, rather than racing with another one.
+    static LAST_ERROR: RefCell<LibWalletError> = RefCell::new(LibWalletError {
+        code: 0,
+        message: String::new(),
+        name: "Unknown",
+        category: LibWalletErrorCategory::Unknown,
+        is_transient: false,
+        source_chain: Vec::new(),
+        available: None,
+        required: None,
+        fee_estimate: None,
+    });
+}
+
+/// Records `err` as this thread's last error, for `wallet_get_last_error_code`/`_message` to surface. Call this at
+/// every point an FFI function is about to collapse a `WalletError`/`HexError`/`ByteArrayError`/`NetAddressError`
+/// into a bare `false`/null return.
+pub fn set_last_error<E: Into<LibWalletError>>(err: E) {
+    let err = err.into();
+    LAST_ERROR.with(|cell| *cell.borrow_mut() = err);
+}
+
+/// The integer code of the last error recorded on this thread via `set_last_error`, or `0` if none has been recorded
+/// yet.
+pub fn last_error_code() -> c_int {
+    LAST_ERROR.with(|cell| cell.borrow().code as c_int)
+}
+
+/// The message of the last error recorded on this thread via `set_last_error`, or an empty string if none has been
+/// recorded yet.
+pub fn last_error_message() -> String {
+    LAST_ERROR.with(|cell| cell.borrow().message.clone())
+}
+
+/// Whether the last error recorded on this thread is worth auto-retrying without changing the call that produced it.
+/// `false` if no error has been recorded yet.
+pub fn last_error_is_transient() -> bool {
+    LAST_ERROR.with(|cell| cell.borrow().is_transient)
+}
+
+/// The source chain of the last error recorded on this thread - one entry per nested error variant peeled on the way
+/// down to the root cause, outermost first - or an empty `Vec` if none has been recorded yet.
+pub fn last_error_source_chain() -> Vec<String> {
+    LAST_ERROR.with(|cell| cell.borrow().source_chain.clone())
+}
+
+/// The balance available, in the last fund-shortfall error recorded on this thread, or `None` if the last error
+/// wasn't a fund shortfall (or no error has been recorded yet).
+pub fn last_error_available() -> Option<u64> {
+    LAST_ERROR.with(|cell| cell.borrow().available)
+}
+
+/// The amount required, in the last fund-shortfall error recorded on this thread, or `None` if the last error wasn't
+/// a fund shortfall (or no error has been recorded yet).
+pub fn last_error_required() -> Option<u64> {
+    LAST_ERROR.with(|cell| cell.borrow().required)
+}
+
+/// The estimated fee, in the last fund-shortfall error recorded on this thread, or `None` if the last error wasn't a
+/// fund shortfall (or no error has been recorded yet).
+pub fn last_error_fee_estimate() -> Option<u64> {
+    LAST_ERROR.with(|cell| cell.borrow().fee_estimate)
+}
+
+/// Patches `available`/`required`/`fee_estimate` onto the last error recorded on this thread, but only if that error
+/// is a fund shortfall (code 101 or 204) - see the `LibWalletError` struct doc for why this has to happen after the
+/// fact rather than inside `From<WalletError>`. Calling this when the last error isn't a fund shortfall (or none has
+/// been recorded) is a no-op, so a caller doesn't need to check the code itself first.
+pub fn set_last_error_funds_detail(available: u64, required: u64, fee_estimate: u64) {
+    LAST_ERROR.with(|cell| {
+        let mut err = cell.borrow_mut();
+        if err.code == 101 || err.code == 204 {
+            err.available = Some(available);
+            err.required = Some(required);
+            err.fee_estimate = Some(fee_estimate);
+        }
+    });
+}
@@ -0,0 +1,57 @@
+// Copyright 2019. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Per-wallet delivery bookkeeping for `wallet_get_pending_events` in `lib.rs`.
+//!
+//! `transaction_service` in this tree exposes no event stream to push into an internal buffer as
+//! transactions arrive, only `get_pending_inbound_transactions()` to poll. So instead of buffering
+//! pushed events, this module buffers *delivery state*: the set of transaction ids already reported
+//! to a caller, per wallet. `wallet_get_pending_events` diffs the currently-pending set against this
+//! and only ever returns ids it hasn't returned before, which is what actually makes repeated polling
+//! behave like a drained queue instead of re-reporting the same events forever.
+//!
+//! Keyed by the wallet's untagged pointer address, the same pattern `lock.rs`/`filelock.rs` use.
+
+extern crate lazy_static;
+
+use lazy_static::lazy_static;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+lazy_static! {
+    static ref DELIVERED: Mutex<HashMap<usize, HashSet<u64>>> = Mutex::new(HashMap::new());
+}
+
+/// Returns the subset of `tx_ids` not already delivered to `wallet_key`, and marks them delivered so
+/// a later call won't return them again.
+pub fn drain_new(wallet_key: usize, tx_ids: &[u64]) -> Vec<u64> {
+    let mut delivered = DELIVERED.lock().unwrap();
+    let seen = delivered.entry(wallet_key).or_insert_with(HashSet::new);
+    tx_ids.iter().copied().filter(|id| seen.insert(*id)).collect()
+}
+
+/// Forgets every id delivered for `wallet_key`. Called from `wallet_destroy`.
+pub fn clear(wallet_key: usize) {
+    DELIVERED.lock().unwrap().remove(&wallet_key);
+}
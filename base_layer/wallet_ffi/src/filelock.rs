@@ -0,0 +1,100 @@
+// Copyright 2019. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Exclusive advisory lock over a wallet's data directory, acquired by `wallet_create` and
+//! released by `wallet_destroy`, so that two `wallet_create` calls - even from separate processes -
+//! can't race on the same LMDB/SQLite files underneath `WalletMemoryDatabase`'s eventual
+//! persistent replacement.
+//!
+//! This uses an OS-level lock (`flock`/`LockFileEx` via the `fs2` crate) on a `.wallet.lock` file
+//! inside the data directory rather than e.g. a PID file: the OS releases the lock automatically
+//! when the holding process exits for any reason, including a crash, so a stale lock can never
+//! block a legitimate restart - which a PID-file scheme would need extra bookkeeping to guarantee.
+//!
+//! Held locks are tracked in a process-wide table keyed by the wallet's untagged pointer address,
+//! the same pattern `lock.rs` uses for passphrase state.
+
+extern crate fs2;
+
+use fs2::FileExt;
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io,
+    path::Path,
+    sync::Mutex,
+};
+
+const LOCK_FILE_NAME: &str = ".wallet.lock";
+
+#[derive(Debug)]
+pub enum LockError {
+    AlreadyLocked,
+    Io(io::Error),
+}
+
+pub struct WalletLock {
+    file: File,
+}
+
+impl Drop for WalletLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+lazy_static! {
+    static ref LOCKS: Mutex<HashMap<usize, WalletLock>> = Mutex::new(HashMap::new());
+}
+
+/// Acquires the exclusive lock for the data directory at `path`, creating the directory and lock
+/// file if they don't already exist. Returns `LockError::AlreadyLocked` if another process (or
+/// another wallet in this process) already holds it.
+///
+/// The returned `WalletLock` releases the lock when dropped; `wallet_create` should `register` it
+/// against the new wallet's pointer on success, or simply let it fall out of scope to release it
+/// immediately if wallet construction fails afterwards.
+pub fn acquire(path: &Path) -> Result<WalletLock, LockError> {
+    std::fs::create_dir_all(path).map_err(LockError::Io)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(path.join(LOCK_FILE_NAME))
+        .map_err(LockError::Io)?;
+    match file.try_lock_exclusive() {
+        Ok(_) => Ok(WalletLock { file }),
+        Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => Err(LockError::AlreadyLocked),
+        Err(e) => Err(LockError::Io(e)),
+    }
+}
+
+/// Registers an already-`acquire`d lock against `wallet_key` so `release(wallet_key)` can find it
+/// again.
+pub fn register(wallet_key: usize, held: WalletLock) {
+    LOCKS.lock().unwrap().insert(wallet_key, held);
+}
+
+/// Releases the lock held for `wallet_key`, if any. Called from `wallet_destroy`.
+pub fn release(wallet_key: usize) {
+    LOCKS.lock().unwrap().remove(&wallet_key);
+}
@@ -0,0 +1,191 @@
+// Copyright 2019. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Hardware-signer backend for a connected Ledger device, built behind the `ledger` Cargo feature
+//! so the default build never links `ledger-transport-hid`/`hidapi`. `LedgerSigner` derives the
+//! node identity public key and signs transaction kernels over HID using the same APDU-exchange
+//! flow as `ledger-transport-hid`'s other wallet integrations; the secret key never leaves the
+//! device.
+//!
+//! This module does not build a `TariCommsConfig` backed by a device-held key: the `TariCommsConfig`
+//! built by `comms_config_create` is keyed off a `NodeIdentity` constructed from an in-memory
+//! `TariPrivateKey` (see `tari_comms::peer_manager::NodeIdentity::new`), and this snapshot has no
+//! public-key-only constructor to build one from a device-held key instead. Rather than ship an FFI
+//! export that can only ever return `ptr::null_mut()` until that constructor exists upstream, the two
+//! operations a Ledger device genuinely supports here - deriving its public key and signing a kernel
+//! with it - are exposed directly as `ledger_get_public_key`/`ledger_sign_kernel`. Once `tari_comms`
+//! grows a public-key-only `NodeIdentity` constructor, a `comms_config_create_with_ledger` wired
+//! through `ledger_get_public_key` can be added back.
+
+use ledger_transport_hid::{hidapi::HidApi, APDUCommand, TransportNativeHID};
+use libc::{c_int, c_uint};
+use tari_utilities::ByteArray;
+
+use crate::{
+    tag::{flip, into_tagged},
+    ByteVector,
+    TariPublicKey,
+};
+
+/// CLA/INS values for the Tari Ledger app's APDU protocol, matching the APDU-based signing flow
+/// `ledger-transport-hid` uses for other wallet crates.
+const CLA: u8 = 0xE0;
+const INS_GET_PUBLIC_KEY: u8 = 0x02;
+const INS_SIGN_KERNEL: u8 = 0x03;
+/// Status word the Ledger app returns when the user declines the on-device prompt.
+const SW_USER_REJECTED: u16 = 0x6985;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TariLedgerError {
+    Success = 0,
+    DeviceNotConnected = 1,
+    UserRejected = 2,
+    ApduError = 3,
+    NotSupported = 4,
+    NullArgument = 5,
+}
+
+impl TariLedgerError {
+    pub fn code(self) -> c_int {
+        self as c_int
+    }
+}
+
+/// A handle to a connected Ledger device running the Tari app, scoped to one BIP32 account index.
+pub struct LedgerSigner {
+    transport: TransportNativeHID,
+    account_index: u32,
+}
+
+impl LedgerSigner {
+    /// Opens a HID connection to the first attached Ledger device. Returns `DeviceNotConnected` if
+    /// none is found or the Tari app isn't open on it.
+    pub fn connect(account_index: u32) -> Result<Self, TariLedgerError> {
+        let api = HidApi::new().map_err(|_| TariLedgerError::DeviceNotConnected)?;
+        let transport = TransportNativeHID::new(&api).map_err(|_| TariLedgerError::DeviceNotConnected)?;
+        Ok(Self {
+            transport,
+            account_index,
+        })
+    }
+
+    /// Asks the device to derive and return the public key for this signer's account index. The
+    /// corresponding secret key never leaves the device.
+    pub fn get_public_key<P: ByteArray>(&self) -> Result<P, TariLedgerError> {
+        let command = APDUCommand {
+            cla: CLA,
+            ins: INS_GET_PUBLIC_KEY,
+            p1: 0x00,
+            p2: 0x00,
+            data: self.account_index.to_be_bytes().to_vec(),
+        };
+        let response = self.transport.exchange(&command).map_err(|_| TariLedgerError::ApduError)?;
+        if response.retcode() == SW_USER_REJECTED {
+            return Err(TariLedgerError::UserRejected);
+        }
+        P::from_bytes(response.data()).map_err(|_| TariLedgerError::ApduError)
+    }
+
+    /// Sends a transaction kernel to the device for signing and returns the signature bytes. The
+    /// device itself prompts the user to approve or reject the spend.
+    pub fn sign_kernel(&self, kernel_bytes: &[u8]) -> Result<Vec<u8>, TariLedgerError> {
+        let command = APDUCommand {
+            cla: CLA,
+            ins: INS_SIGN_KERNEL,
+            p1: 0x00,
+            p2: 0x00,
+            data: kernel_bytes.to_vec(),
+        };
+        let response = self.transport.exchange(&command).map_err(|_| TariLedgerError::ApduError)?;
+        if response.retcode() == SW_USER_REJECTED {
+            return Err(TariLedgerError::UserRejected);
+        }
+        Ok(response.data().to_vec())
+    }
+}
+
+/// Connects to a Ledger device and derives the public key for `account_index`. The corresponding
+/// secret key never leaves the device.
+///
+/// ## Arguments
+/// `account_index` - The BIP32 account index to derive the public key from
+/// `error_out` - Pointer to an int which will be modified to a `TariLedgerError` code (distinct
+/// from the general `TariFfiError` codes used elsewhere in this crate) should one occur, may be
+/// null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `*mut TariPublicKey` - Returns the device's public key for `account_index`. Note that it will be
+/// ptr::null_mut() if the device isn't connected, the user rejects the prompt, or the exchange fails
+#[no_mangle]
+pub unsafe extern "C" fn ledger_get_public_key(account_index: c_uint, error_out: *mut c_int) -> *mut TariPublicKey {
+    let result = LedgerSigner::connect(account_index).and_then(|signer| signer.get_public_key::<TariPublicKey>());
+    match result {
+        Ok(public_key) => into_tagged(public_key),
+        Err(e) => {
+            if !error_out.is_null() {
+                *error_out = e.code();
+            }
+            core::ptr::null_mut()
+        },
+    }
+}
+
+/// Connects to a Ledger device and asks it to sign `kernel_bytes`, the same transaction kernel a
+/// software signer would sign over. The device itself prompts the user to approve or reject the
+/// spend before returning a signature.
+///
+/// ## Arguments
+/// `account_index` - The BIP32 account index to sign with
+/// `kernel_bytes` - The pointer to a ByteVector holding the transaction kernel to sign
+/// `error_out` - Pointer to an int which will be modified to a `TariLedgerError` code (distinct
+/// from the general `TariFfiError` codes used elsewhere in this crate) should one occur, may be
+/// null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `*mut ByteVector` - Returns the signature bytes. Note that it will be ptr::null_mut() if
+/// `kernel_bytes` is null, the device isn't connected, the user rejects the prompt, or the exchange
+/// fails
+#[no_mangle]
+pub unsafe extern "C" fn ledger_sign_kernel(
+    account_index: c_uint,
+    kernel_bytes: *mut ByteVector,
+    error_out: *mut c_int,
+) -> *mut ByteVector {
+    let kernel_bytes = flip(kernel_bytes);
+    if kernel_bytes.is_null() {
+        if !error_out.is_null() {
+            *error_out = TariLedgerError::NullArgument.code();
+        }
+        return core::ptr::null_mut();
+    }
+    let result = LedgerSigner::connect(account_index).and_then(|signer| signer.sign_kernel(&(*kernel_bytes).0));
+    match result {
+        Ok(signature) => into_tagged(ByteVector(signature)),
+        Err(e) => {
+            if !error_out.is_null() {
+                *error_out = e.code();
+            }
+            core::ptr::null_mut()
+        },
+    }
+}
@@ -28,13 +28,33 @@
 //! This files contians the API calls that will be exposed to external systems that make use of this module. The API
 //! will be exposed via FFI and will consist of API calls that the FFI client can make into the Wallet module and a set
 //! of Callbacks that the client must implement and provide to the Wallet module to receive asynchronous replies and
-//! updates.
+//! updates. For clients where implementing a callback is awkward (e.g. garbage-collected or single-threaded host
+//! languages), `wallet_get_pending_events` offers a pull-based alternative: the wallet buffers events internally and
+//! the client drains them on its own schedule instead.
+extern crate bincode;
 extern crate libc;
 extern crate tari_wallet;
 
+mod backup;
+mod coin_selection;
+mod dart;
+mod error;
+mod events;
+mod filelock;
+#[cfg(feature = "ledger")]
+mod ledger;
+mod lock;
+mod peer_alias;
+mod seed_words;
+mod tag;
+
+use coin_selection::TariCoinSelectionStrategy;
+use error::{set_error, set_last_error, TariFfiError};
+use tag::{from_tagged, into_tagged};
 use libc::{c_char, c_uint, c_int, c_longlong, c_uchar, c_ulonglong};
 use std::{
     boxed::Box,
+    convert::TryInto,
     ffi::{CStr, CString},
     slice,
 };
@@ -51,6 +71,8 @@ use tari_crypto::keys::PublicKey;
 use tari_utilities::hex::Hex;
 use tari_wallet::{
     contacts_service::storage::database::Contact,
+    error::WalletError,
+    output_manager_service::error::OutputManagerError,
     storage::memory_db::WalletMemoryDatabase,
     test_utils::generate_wallet_test_data,
 };
@@ -70,6 +92,132 @@ pub type TariPendingOutboundTransaction = tari_wallet::transaction_service::stor
 pub struct TariPendingOutboundTransactions(Vec<TariPendingOutboundTransaction>);
 pub struct ByteVector(Vec<c_uchar>); // declared like this so that it can be exposed to external header
 
+pub struct TariSeedWords(Vec<String>);
+
+/// A simple string collection, used by `liberror_source_chain` so a client can walk an error's cause chain without a
+/// dedicated wrapper type per use case.
+pub struct TariStrings(Vec<String>);
+
+/// A distinct `error_out` code space for `wallet_create_from_seed_words`, separate from the
+/// general `TariFfiError` codes used elsewhere in this crate.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TariSeedWordsError {
+    Success = 0,
+    InvalidWordCount = 1,
+    UnknownWord = 2,
+    ChecksumMismatch = 3,
+}
+
+impl From<seed_words::SeedWordsError> for TariSeedWordsError {
+    fn from(e: seed_words::SeedWordsError) -> Self {
+        match e {
+            seed_words::SeedWordsError::InvalidWordCount => TariSeedWordsError::InvalidWordCount,
+            seed_words::SeedWordsError::UnknownWord => TariSeedWordsError::UnknownWord,
+            seed_words::SeedWordsError::ChecksumMismatch => TariSeedWordsError::ChecksumMismatch,
+        }
+    }
+}
+
+/// A distinct `error_out` code space for `wallet_add_base_node_peer_by_name`, separate from the
+/// general `TariFfiError` codes used elsewhere in this crate.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TariPeerAliasError {
+    Success = 0,
+    NotFound = 1,
+}
+
+impl From<peer_alias::PeerAliasError> for TariPeerAliasError {
+    fn from(e: peer_alias::PeerAliasError) -> Self {
+        match e {
+            peer_alias::PeerAliasError::NotFound => TariPeerAliasError::NotFound,
+        }
+    }
+}
+
+/// The FFI mirror of `error::LibWalletErrorCategory`, returned by `liberror_category` so a client can branch on a
+/// small stable category set instead of embedding a copy of the full `LibWalletError` code table.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TariLibWalletErrorCategory {
+    Funds = 0,
+    TransactionProtocol = 1,
+    Storage = 2,
+    Network = 3,
+    Encoding = 4,
+    Unknown = 5,
+}
+
+impl From<error::LibWalletErrorCategory> for TariLibWalletErrorCategory {
+    fn from(c: error::LibWalletErrorCategory) -> Self {
+        match c {
+            error::LibWalletErrorCategory::Funds => TariLibWalletErrorCategory::Funds,
+            error::LibWalletErrorCategory::TransactionProtocol => TariLibWalletErrorCategory::TransactionProtocol,
+            error::LibWalletErrorCategory::Storage => TariLibWalletErrorCategory::Storage,
+            error::LibWalletErrorCategory::Network => TariLibWalletErrorCategory::Network,
+            error::LibWalletErrorCategory::Encoding => TariLibWalletErrorCategory::Encoding,
+            error::LibWalletErrorCategory::Unknown => TariLibWalletErrorCategory::Unknown,
+        }
+    }
+}
+
+/// The kind of event buffered for `wallet_get_pending_events`. Only `TransactionReceived` is
+/// currently emitted; `TransactionMined`/`BaseNodeSyncProgress` are never emitted, since the
+/// transaction service here exposes no mined/sync events to observe (see the note after the
+/// callback registration functions further down this file).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TariEventType {
+    TransactionReceived = 0,
+    TransactionMined = 1,
+    BaseNodeSyncProgress = 2,
+}
+
+/// A single buffered wallet event. This is a flat, fixed-shape struct rather than a real tagged
+/// union so it stays trivially `#[repr(C)]` for every client language; only the fields relevant
+/// to `event_type` are meaningful, the rest are zeroed.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TariEvent {
+    pub event_type: TariEventType,
+    pub tx_id: c_ulonglong,
+    pub sync_current: c_ulonglong,
+    pub sync_total: c_ulonglong,
+}
+pub struct TariEvents(Vec<TariEvent>);
+
+/// A C-ABI-safe `Option<u64>`. Getters that wrap a genuinely optional numeric field return this
+/// instead of falling back to `0`, so a client can tell "the field is zero" from "the field was
+/// never set" (the latter sets `is_some` to `false` and `value` to `0`).
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct COption_u64 {
+    pub is_some: bool,
+    pub value: u64,
+}
+
+impl COption_u64 {
+    fn some(value: u64) -> Self {
+        COption_u64 { is_some: true, value }
+    }
+
+    fn none() -> Self {
+        COption_u64 { is_some: false, value: 0 }
+    }
+}
+
+/// A C-ABI-safe `Option<ByteVector>`, reserved for getters that wrap a genuinely optional byte or
+/// string field (e.g. a transaction message) once one is exposed on `TariCompletedTransaction`; no
+/// accessor in this file returns one yet.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+pub struct COption_Bytes {
+    pub is_some: bool,
+    pub bytes: *mut ByteVector,
+}
+
 /// -------------------------------- Strings ------------------------------------------------ ///
 
 /// Destroys a char array
@@ -95,24 +243,33 @@ pub unsafe extern "C" fn string_destroy(ptr: *mut c_char) {
 /// ## Arguments
 /// `byte_array` - The pointer to the byte array
 /// `element_count` - The number of elements in byte_array
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
 ///
 /// ## Returns
 /// `*mut ByteVector` - Pointer to the created ByteVector. Note that it will be ptr::null_mut()
 /// if the byte_array pointer was null or if the elements in the byte_vector don't match
 /// element_count when it is created
 #[no_mangle]
-pub unsafe extern "C" fn byte_vector_create(byte_array: *const c_uchar, element_count: c_uint) -> *mut ByteVector {
+pub unsafe extern "C" fn byte_vector_create(
+    byte_array: *const c_uchar,
+    element_count: c_uint,
+    error_out: *mut c_int,
+) -> *mut ByteVector
+{
     let mut bytes = ByteVector(Vec::new());
     if byte_array.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
         return ptr::null_mut();
     } else {
         let array: &[c_uchar] = slice::from_raw_parts(byte_array, element_count as usize);
         bytes.0 = array.to_vec();
         if bytes.0.len() != element_count as usize {
+            set_error(error_out, TariFfiError::InvalidLength);
             return ptr::null_mut();
         }
     }
-    Box::into_raw(Box::new(bytes))
+    into_tagged(bytes)
 }
 
 /// Destroys a ByteVector
@@ -124,9 +281,7 @@ pub unsafe extern "C" fn byte_vector_create(byte_array: *const c_uchar, element_
 /// `()` - Does not return a value, equivalent to void in C
 #[no_mangle]
 pub unsafe extern "C" fn byte_vector_destroy(bytes: *mut ByteVector) {
-    if bytes.is_null() {
-        Box::from_raw(bytes);
-    }
+    from_tagged(bytes);
 }
 
 /// Gets a c_uchar at position in a ByteVector
@@ -143,13 +298,14 @@ pub unsafe extern "C" fn byte_vector_get_at(ptr: *mut ByteVector, position: c_ui
     if ptr.is_null() {
         return 0 as c_uchar;
     }
-    let len= byte_vector_get_length(ptr) as c_int - 1; // clamp to length
+    let len = byte_vector_get_length(ptr) as c_int - 1; // clamp to length, ptr is still tagged here
     if len < 0 {
         return 0 as c_uchar;
     }
     if position > len as c_uint {
         return 0 as c_uchar;
     }
+    let ptr = tag::flip(ptr);
     (*ptr).0.clone()[position as usize]
 }
 
@@ -166,6 +322,7 @@ pub unsafe extern "C" fn byte_vector_get_length(vec: *const ByteVector) -> c_uin
     if vec.is_null() {
         return 0;
     }
+    let vec = tag::flip(vec as *mut ByteVector) as *const ByteVector;
     (&*vec).0.len() as c_uint
 }
 
@@ -177,22 +334,29 @@ pub unsafe extern "C" fn byte_vector_get_length(vec: *const ByteVector) -> c_uin
 ///
 /// ## Arguments
 /// `bytes` - The pointer to a ByteVector
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
 ///
 /// ## Returns
 /// `TariPublicKey` - Returns a public key. Note that it will be ptr::null_mut() if bytes is null or
 /// if there was an error with the contents of bytes
 #[no_mangle]
-pub unsafe extern "C" fn public_key_create(bytes: *mut ByteVector) -> *mut TariPublicKey {
+pub unsafe extern "C" fn public_key_create(bytes: *mut ByteVector, error_out: *mut c_int) -> *mut TariPublicKey {
+    let bytes = tag::flip(bytes);
     let v;
     if !bytes.is_null() {
         v = (*bytes).0.clone();
     } else {
+        set_error(error_out, TariFfiError::NullArgument);
         return ptr::null_mut();
     }
     let pk = TariPublicKey::from_bytes(&v);
     match pk {
-        Ok(pk) => Box::into_raw(Box::new(pk)),
-        Err(_) => ptr::null_mut(),
+        Ok(pk) => into_tagged(pk),
+        Err(_) => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            ptr::null_mut()
+        },
     }
 }
 
@@ -205,9 +369,7 @@ pub unsafe extern "C" fn public_key_create(bytes: *mut ByteVector) -> *mut TariP
 /// `()` - Does not return a value, equivalent to void in C
 #[no_mangle]
 pub unsafe extern "C" fn public_key_destroy(pk: *mut TariPublicKey) {
-    if !pk.is_null() {
-        Box::from_raw(pk);
-    }
+    from_tagged(pk);
 }
 
 /// Gets a ByteVector from a TariPublicKey
@@ -219,13 +381,14 @@ pub unsafe extern "C" fn public_key_destroy(pk: *mut TariPublicKey) {
 /// `*mut ByteVector` - Returns a pointer to a ByteVector. Note that it returns ptr::null_mut() if pk is null
 #[no_mangle]
 pub unsafe extern "C" fn public_key_get_bytes(pk: *mut TariPublicKey) -> *mut ByteVector {
+    let pk = tag::flip(pk);
     let mut bytes = ByteVector(Vec::new());
     if !pk.is_null() {
         bytes.0 = (*pk).to_vec();
     } else {
         return ptr::null_mut();
     }
-    Box::into_raw(Box::new(bytes))
+    into_tagged(bytes)
 }
 
 /// Creates a TariPublicKey from a TariPrivateKey
@@ -237,34 +400,41 @@ pub unsafe extern "C" fn public_key_get_bytes(pk: *mut TariPublicKey) -> *mut By
 /// `*mut TariPublicKey` - Returns a pointer to a TariPublicKey
 #[no_mangle]
 pub unsafe extern "C" fn public_key_from_private_key(secret_key: *mut TariPrivateKey) -> *mut TariPublicKey {
+    let secret_key = tag::flip(secret_key);
     if secret_key.is_null() {
         return ptr::null_mut();
     }
     let m = TariPublicKey::from_secret_key(&(*secret_key));
-    Box::into_raw(Box::new(m))
+    into_tagged(m)
 }
 
 /// Creates a TariPublicKey from a char array
 ///
 /// ## Arguments
 /// `key` - The pointer to a char array
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
 ///
 /// ## Returns
 /// `*mut TariPublicKey` - Returns a pointer to a TariPublicKey. Note that it returns ptr::null_mut()
 /// if key is null or if there was an error creating the TariPublicKey from key
 #[no_mangle]
-pub unsafe extern "C" fn public_key_from_hex(key: *const c_char) -> *mut TariPublicKey {
+pub unsafe extern "C" fn public_key_from_hex(key: *const c_char, error_out: *mut c_int) -> *mut TariPublicKey {
     let key_str;
     if !key.is_null() {
         key_str = CStr::from_ptr(key).to_str().unwrap().to_owned();
     } else {
+        set_error(error_out, TariFfiError::NullArgument);
         return ptr::null_mut();
     }
 
     let public_key = TariPublicKey::from_hex(key_str.as_str());
     match public_key {
-        Ok(public_key) => Box::into_raw(Box::new(public_key)),
-        Err(_) => ptr::null_mut(),
+        Ok(public_key) => into_tagged(public_key),
+        Err(_) => {
+            set_error(error_out, TariFfiError::InvalidHex);
+            ptr::null_mut()
+        },
     }
 }
 /// -------------------------------------------------------------------------------------------- ///
@@ -275,22 +445,29 @@ pub unsafe extern "C" fn public_key_from_hex(key: *const c_char) -> *mut TariPub
 ///
 /// ## Arguments
 /// `bytes` - The pointer to a ByteVector
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
 ///
 /// ## Returns
 /// `*mut TariPrivateKey` - Returns a pointer to a TariPublicKey. Note that it returns ptr::null_mut()
 /// if bytes is null or if there was an error creating the TariPrivateKey from bytes
 #[no_mangle]
-pub unsafe extern "C" fn private_key_create(bytes: *mut ByteVector) -> *mut TariPrivateKey {
+pub unsafe extern "C" fn private_key_create(bytes: *mut ByteVector, error_out: *mut c_int) -> *mut TariPrivateKey {
+    let bytes = tag::flip(bytes);
     let v;
     if !bytes.is_null() {
         v = (*bytes).0.clone();
     } else {
+        set_error(error_out, TariFfiError::NullArgument);
         return ptr::null_mut();
     }
     let pk = TariPrivateKey::from_bytes(&v);
     match pk {
-        Ok(pk) => Box::into_raw(Box::new(pk)),
-        Err(_) => ptr::null_mut(),
+        Ok(pk) => into_tagged(pk),
+        Err(_) => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            ptr::null_mut()
+        },
     }
 }
 
@@ -303,9 +480,7 @@ pub unsafe extern "C" fn private_key_create(bytes: *mut ByteVector) -> *mut Tari
 /// `()` - Does not return a value, equivalent to void in C
 #[no_mangle]
 pub unsafe extern "C" fn private_key_destroy(pk: *mut TariPrivateKey) {
-    if !pk.is_null() {
-        Box::from_raw(pk);
-    }
+    from_tagged(pk);
 }
 
 /// Gets a ByteVector from a TariPrivateKey
@@ -318,13 +493,14 @@ pub unsafe extern "C" fn private_key_destroy(pk: *mut TariPrivateKey) {
 /// if pk is null
 #[no_mangle]
 pub unsafe extern "C" fn private_key_get_bytes(pk: *mut TariPrivateKey) -> *mut ByteVector {
+    let pk = tag::flip(pk);
     let mut bytes = ByteVector(Vec::new());
     if !pk.is_null() {
         bytes.0 = (*pk).to_vec();
     } else {
         return ptr::null_mut();
     }
-    Box::into_raw(Box::new(bytes))
+    into_tagged(bytes)
 }
 
 /// Generates a TariPrivateKey
@@ -338,31 +514,37 @@ pub unsafe extern "C" fn private_key_get_bytes(pk: *mut TariPrivateKey) -> *mut
 pub unsafe extern "C" fn private_key_generate() -> *mut TariPrivateKey {
     let mut rng = rand::OsRng::new().unwrap();
     let secret_key = TariPrivateKey::random(&mut rng);
-    Box::into_raw(Box::new(secret_key))
+    into_tagged(secret_key)
 }
 
 /// Creates a TariPrivateKey from a char array
 ///
 /// ## Arguments
 /// `key` - The pointer to a char array
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
 ///
 /// ## Returns
 /// `*mut TariPrivateKey` - Returns a pointer to a TariPublicKey. Note that it returns ptr::null_mut()
 /// if key is null or if there was an error creating the TariPrivateKey from key
 #[no_mangle]
-pub unsafe extern "C" fn private_key_from_hex(key: *const c_char) -> *mut TariPrivateKey {
+pub unsafe extern "C" fn private_key_from_hex(key: *const c_char, error_out: *mut c_int) -> *mut TariPrivateKey {
     let key_str;
     if !key.is_null() {
         key_str = CStr::from_ptr(key).to_str().unwrap().to_owned();
     } else {
+        set_error(error_out, TariFfiError::NullArgument);
         return ptr::null_mut();
     }
 
     let secret_key = TariPrivateKey::from_hex(key_str.as_str());
 
     match secret_key {
-        Ok(secret_key) => Box::into_raw(Box::new(secret_key)),
-        Err(_) => ptr::null_mut(),
+        Ok(secret_key) => into_tagged(secret_key),
+        Err(_) => {
+            set_error(error_out, TariFfiError::InvalidHex);
+            ptr::null_mut()
+        },
     }
 }
 
@@ -381,6 +563,7 @@ pub unsafe extern "C" fn private_key_from_hex(key: *const c_char) -> *mut TariPr
 /// if alias is null or if pk is null
 #[no_mangle]
 pub unsafe extern "C" fn contact_create(alias: *const c_char, public_key: *mut TariPublicKey) -> *mut TariContact {
+    let public_key = tag::flip(public_key);
     let alias_string;
     if !alias.is_null() {
         alias_string = CStr::from_ptr(alias).to_str().unwrap().to_owned();
@@ -396,7 +579,7 @@ pub unsafe extern "C" fn contact_create(alias: *const c_char, public_key: *mut T
         alias: alias_string.to_string(),
         public_key: (*public_key).clone(),
     };
-    Box::into_raw(Box::new(contact))
+    into_tagged(contact)
 }
 
 /// Gets the alias of the TariContact
@@ -409,6 +592,7 @@ pub unsafe extern "C" fn contact_create(alias: *const c_char, public_key: *mut T
 /// contact is null
 #[no_mangle]
 pub unsafe extern "C" fn contact_get_alias(contact: *mut TariContact) -> *mut c_char {
+    let contact = tag::flip(contact);
     let mut a = CString::new("").unwrap();
     if !contact.is_null() {
         a = CString::new((*contact).alias.clone()).unwrap();
@@ -426,10 +610,11 @@ pub unsafe extern "C" fn contact_get_alias(contact: *mut TariContact) -> *mut c_
 /// ptr::null_mut() if contact is null
 #[no_mangle]
 pub unsafe extern "C" fn contact_get_public_key(contact: *mut TariContact) -> *mut TariPublicKey {
+    let contact = tag::flip(contact);
     if contact.is_null() {
         return ptr::null_mut();
     }
-    Box::into_raw(Box::new((*contact).public_key.clone()))
+    into_tagged((*contact).public_key.clone())
 }
 
 /// Destroys the TariContact
@@ -441,9 +626,85 @@ pub unsafe extern "C" fn contact_get_public_key(contact: *mut TariContact) -> *m
 /// `()` - Does not return a value, equivalent to void in C
 #[no_mangle]
 pub unsafe extern "C" fn contact_destroy(contact: *mut TariContact) {
-    if !contact.is_null() {
-        Box::from_raw(contact);
+    from_tagged(contact);
+}
+
+/// Serializes a TariContact to a ByteVector so it can be persisted or moved between devices. The
+/// encoding is a versioned, hand-rolled format (leading format-version byte) rather than relying on
+/// an external serializer, so `contact_from_bytes` stays able to read buffers written by older
+/// versions of this function.
+///
+/// ## Arguments
+/// `contact` - The pointer to a TariContact
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `*mut ByteVector` - Returns a pointer to a ByteVector. Note that it returns ptr::null_mut() if
+/// contact is null
+#[no_mangle]
+pub unsafe extern "C" fn contact_to_bytes(contact: *mut TariContact, error_out: *mut c_int) -> *mut ByteVector {
+    let contact = tag::flip(contact);
+    if contact.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
+        return ptr::null_mut();
+    }
+    let pk_bytes = (*contact).public_key.to_vec();
+    let alias_bytes = (*contact).alias.as_bytes();
+
+    let mut buf = Vec::with_capacity(1 + 4 + pk_bytes.len() + 4 + alias_bytes.len());
+    buf.push(1u8); // format version
+    buf.extend_from_slice(&(pk_bytes.len() as u32).to_be_bytes());
+    buf.extend_from_slice(&pk_bytes);
+    buf.extend_from_slice(&(alias_bytes.len() as u32).to_be_bytes());
+    buf.extend_from_slice(alias_bytes);
+
+    into_tagged(ByteVector(buf))
+}
+
+/// Deserializes a TariContact previously produced by `contact_to_bytes`.
+///
+/// ## Arguments
+/// `bytes` - The pointer to a ByteVector
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `*mut TariContact` - Returns a pointer to a TariContact. Note that it returns ptr::null_mut() if
+/// bytes is null or the buffer is not a valid encoding
+#[no_mangle]
+pub unsafe extern "C" fn contact_from_bytes(bytes: *mut ByteVector, error_out: *mut c_int) -> *mut TariContact {
+    let bytes = tag::flip(bytes);
+    if bytes.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
+        return ptr::null_mut();
+    }
+    let buf = &(*bytes).0;
+    match decode_contact(buf) {
+        Some(contact) => into_tagged(contact),
+        None => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            ptr::null_mut()
+        },
+    }
+}
+
+fn decode_contact(buf: &[u8]) -> Option<TariContact> {
+    if buf.is_empty() || buf[0] != 1 {
+        return None;
     }
+    let mut pos = 1usize;
+    let pk_len = u32::from_be_bytes(buf.get(pos..pos + 4)?.try_into().ok()?) as usize;
+    pos += 4;
+    let pk_bytes = buf.get(pos..pos + pk_len)?;
+    pos += pk_len;
+    let alias_len = u32::from_be_bytes(buf.get(pos..pos + 4)?.try_into().ok()?) as usize;
+    pos += 4;
+    let alias_bytes = buf.get(pos..pos + alias_len)?;
+
+    let public_key = TariPublicKey::from_bytes(pk_bytes).ok()?;
+    let alias = String::from_utf8(alias_bytes.to_vec()).ok()?;
+    Some(Contact { alias, public_key })
 }
 
 /// ----------------------------------- Contacts -------------------------------------------------///
@@ -459,6 +720,7 @@ pub unsafe extern "C" fn contact_destroy(contact: *mut TariContact) {
 pub unsafe extern "C" fn contacts_get_length(contacts: *mut TariContacts) -> c_uint {
     let mut len = 0;
     if !contacts.is_null() {
+        let contacts = tag::flip(contacts);
         len = (*contacts).0.len();
     }
     len as c_uint
@@ -469,23 +731,30 @@ pub unsafe extern "C" fn contacts_get_length(contacts: *mut TariContacts) -> c_u
 /// ## Arguments
 /// `contacts` - The pointer to a TariContacts
 /// `position` - The integer position
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
 ///
 /// ## Returns
 /// `*mut TariContact` - Returns a TariContact, note that it returns ptr::null_mut() if contacts is
 /// null or position is invalid
 #[no_mangle]
-pub unsafe extern "C" fn contacts_get_at(contacts: *mut TariContacts, position: c_uint) -> *mut TariContact {
+pub unsafe extern "C" fn contacts_get_at(
+    contacts: *mut TariContacts,
+    position: c_uint,
+    error_out: *mut c_int,
+) -> *mut TariContact
+{
     if contacts.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
         return ptr::null_mut();
     }
     let len = contacts_get_length(contacts) as c_int - 1;
-    if len < 0 {
+    if len < 0 || position > len as c_uint {
+        set_error(error_out, TariFfiError::IndexOutOfBounds);
         return ptr::null_mut();
     }
-    if position > len as c_uint {
-        return ptr::null_mut();
-    }
-    Box::into_raw(Box::new((*contacts).0[position as usize].clone()))
+    let contacts = tag::flip(contacts);
+    into_tagged((*contacts).0[position as usize].clone())
 }
 
 /// Destroys the TariContacts
@@ -497,9 +766,7 @@ pub unsafe extern "C" fn contacts_get_at(contacts: *mut TariContacts, position:
 /// `()` - Does not return a value, equivalent to void in C
 #[no_mangle]
 pub unsafe extern "C" fn contacts_destroy(contacts: *mut TariContacts) {
-    if !contacts.is_null() {
-        Box::from_raw(contacts);
-    }
+    from_tagged(contacts);
 }
 
 /// -------------------------------------------------------------------------------------------- ///
@@ -518,6 +785,7 @@ pub unsafe extern "C" fn contacts_destroy(contacts: *mut TariContacts) {
 pub unsafe extern "C" fn completed_transactions_get_length(transactions: *mut TariCompletedTransactions) -> c_uint {
     let mut len = 0;
     if !transactions.is_null() {
+        let transactions = tag::flip(transactions);
         len = (*transactions).0.len();
     }
     len as c_uint
@@ -528,6 +796,8 @@ pub unsafe extern "C" fn completed_transactions_get_length(transactions: *mut Ta
 /// ## Arguments
 /// `transactions` - The pointer to a TariCompletedTransactions
 /// `position` - The integer position
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
 ///
 /// ## Returns
 /// `*mut TariCompletedTransaction` - Returns a pointer to a TariCompletedTransaction,
@@ -536,19 +806,20 @@ pub unsafe extern "C" fn completed_transactions_get_length(transactions: *mut Ta
 pub unsafe extern "C" fn completed_transactions_get_at(
     transactions: *mut TariCompletedTransactions,
     position: c_uint,
+    error_out: *mut c_int,
 ) -> *mut TariCompletedTransaction
 {
     if transactions.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
         return ptr::null_mut();
     }
     let len = completed_transactions_get_length(transactions) as c_int - 1;
-    if len < 0 {
-        return ptr::null_mut();
-    }
-    if position > len as c_uint {
+    if len < 0 || position > len as c_uint {
+        set_error(error_out, TariFfiError::IndexOutOfBounds);
         return ptr::null_mut();
     }
-    Box::into_raw(Box::new((*transactions).0[position as usize].clone()))
+    let transactions = tag::flip(transactions);
+    into_tagged((*transactions).0[position as usize].clone())
 }
 
 /// Destroys a TariCompletedTransactions
@@ -560,9 +831,7 @@ pub unsafe extern "C" fn completed_transactions_get_at(
 /// `()` - Does not return a value, equivalent to void in C
 #[no_mangle]
 pub unsafe extern "C" fn completed_transactions_destroy(transactions: *mut TariCompletedTransactions) {
-    if !transactions.is_null() {
-        Box::from_raw(transactions);
-    }
+    from_tagged(transactions);
 }
 
 /// -------------------------------------------------------------------------------------------- ///
@@ -583,6 +852,7 @@ pub unsafe extern "C" fn pending_outbound_transactions_get_length(
 ) -> c_uint {
     let mut len = 0;
     if !transactions.is_null() {
+        let transactions = tag::flip(transactions);
         len = (*transactions).0.len();
     }
     len as c_uint
@@ -593,6 +863,8 @@ pub unsafe extern "C" fn pending_outbound_transactions_get_length(
 /// ## Arguments
 /// `transactions` - The pointer to a TariPendingOutboundTransactions
 /// `position` - The integer position
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
 ///
 /// ## Returns
 /// `*mut TariPendingOutboundTransaction` - Returns a pointer to a TariPendingOutboundTransaction,
@@ -601,19 +873,20 @@ pub unsafe extern "C" fn pending_outbound_transactions_get_length(
 pub unsafe extern "C" fn pending_outbound_transactions_get_at(
     transactions: *mut TariPendingOutboundTransactions,
     position: c_uint,
+    error_out: *mut c_int,
 ) -> *mut TariPendingOutboundTransaction
 {
     if transactions.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
         return ptr::null_mut();
     }
     let len = pending_outbound_transactions_get_length(transactions) as c_int - 1;
-    if len < 0 {
+    if len < 0 || position > len as c_uint {
+        set_error(error_out, TariFfiError::IndexOutOfBounds);
         return ptr::null_mut();
     }
-    if position > len as c_uint {
-        return ptr::null_mut();
-    }
-    Box::into_raw(Box::new((*transactions).0[position as usize].clone()))
+    let transactions = tag::flip(transactions);
+    into_tagged((*transactions).0[position as usize].clone())
 }
 
 /// Destroys a TariCompletedTransactions
@@ -625,9 +898,7 @@ pub unsafe extern "C" fn pending_outbound_transactions_get_at(
 /// `()` - Does not return a value, equivalent to void in C
 #[no_mangle]
 pub unsafe extern "C" fn pending_outbound_transactions_destroy(transactions: *mut TariPendingOutboundTransactions) {
-    if !transactions.is_null() {
-        Box::from_raw(transactions);
-    }
+    from_tagged(transactions);
 }
 
 /// -------------------------------------------------------------------------------------------- ///
@@ -648,6 +919,7 @@ pub unsafe extern "C" fn pending_inbound_transactions_get_length(
 ) -> c_uint {
     let mut len = 0;
     if !transactions.is_null() {
+        let transactions = tag::flip(transactions);
         len = (*transactions).0.len();
     }
     len as c_uint
@@ -658,6 +930,8 @@ pub unsafe extern "C" fn pending_inbound_transactions_get_length(
 /// ## Arguments
 /// `transactions` - The pointer to a TariPendingInboundTransactions
 /// `position` - The integer position
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
 ///
 /// ## Returns
 /// `*mut TariPendingOutboundTransaction` - Returns a pointer to a TariPendingInboundTransaction,
@@ -666,19 +940,20 @@ pub unsafe extern "C" fn pending_inbound_transactions_get_length(
 pub unsafe extern "C" fn pending_inbound_transactions_get_at(
     transactions: *mut TariPendingInboundTransactions,
     position: c_uint,
+    error_out: *mut c_int,
 ) -> *mut TariPendingInboundTransaction
 {
     if transactions.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
         return ptr::null_mut();
     }
     let len = pending_inbound_transactions_get_length(transactions) as c_int - 1;
-    if len < 0 {
-        return ptr::null_mut();
-    }
-    if position > len as c_uint {
+    if len < 0 || position > len as c_uint {
+        set_error(error_out, TariFfiError::IndexOutOfBounds);
         return ptr::null_mut();
     }
-    Box::into_raw(Box::new((*transactions).0[position as usize].clone()))
+    let transactions = tag::flip(transactions);
+    into_tagged((*transactions).0[position as usize].clone())
 }
 
 /// Destroys a TariCompletedTransactions
@@ -690,9 +965,7 @@ pub unsafe extern "C" fn pending_inbound_transactions_get_at(
 /// `()` - Does not return a value, equivalent to void in C
 #[no_mangle]
 pub unsafe extern "C" fn pending_inbound_transactions_destroy(transactions: *mut TariPendingInboundTransactions) {
-    if !transactions.is_null() {
-        Box::from_raw(transactions);
-    }
+    from_tagged(transactions);
 }
 
 /// -------------------------------------------------------------------------------------------- ///
@@ -705,15 +978,16 @@ pub unsafe extern "C" fn pending_inbound_transactions_destroy(transactions: *mut
 /// `transaction` - The pointer to a TariCompletedTransaction
 ///
 /// ## Returns
-/// `c_ulonglong` - Returns the TransactionID, note that it will be zero if transaction is null
+/// `COption_u64` - Returns the TransactionID, with `is_some` false if transaction is null
 #[no_mangle]
 pub unsafe extern "C" fn completed_transaction_get_transaction_id(
     transaction: *mut TariCompletedTransaction,
-) -> c_ulonglong {
+) -> COption_u64 {
+    let transaction = tag::flip(transaction);
     if transaction.is_null() {
-        return 0;
+        return COption_u64::none();
     }
-    (*transaction).tx_id as c_ulonglong
+    COption_u64::some((*transaction).tx_id as u64)
 }
 
 /// Gets the destination TariPublicKey of a TariCompletedTransaction
@@ -728,11 +1002,12 @@ pub unsafe extern "C" fn completed_transaction_get_transaction_id(
 pub unsafe extern "C" fn completed_transaction_get_destination_public_key(
     transaction: *mut TariCompletedTransaction,
 ) -> *mut TariPublicKey {
+    let transaction = tag::flip(transaction);
     if transaction.is_null() {
         return ptr::null_mut();
     }
     let m = (*transaction).destination_public_key.clone();
-    Box::into_raw(Box::new(m))
+    into_tagged(m)
 }
 
 /// Gets the amount of a TariCompletedTransaction
@@ -741,13 +1016,14 @@ pub unsafe extern "C" fn completed_transaction_get_destination_public_key(
 /// `transaction` - The pointer to a TariCompletedTransaction
 ///
 /// ## Returns
-/// `c_ulonglong` - Returns the amount, note that it will be zero if transaction is null
+/// `COption_u64` - Returns the amount, with `is_some` false if transaction is null
 #[no_mangle]
-pub unsafe extern "C" fn completed_transaction_get_amount(transaction: *mut TariCompletedTransaction) -> c_ulonglong {
+pub unsafe extern "C" fn completed_transaction_get_amount(transaction: *mut TariCompletedTransaction) -> COption_u64 {
+    let transaction = tag::flip(transaction);
     if transaction.is_null() {
-        return 0;
+        return COption_u64::none();
     }
-    c_ulonglong::from((*transaction).amount)
+    COption_u64::some(c_ulonglong::from((*transaction).amount))
 }
 
 /// Gets the fee of a TariCompletedTransaction
@@ -756,17 +1032,21 @@ pub unsafe extern "C" fn completed_transaction_get_amount(transaction: *mut Tari
 /// `transaction` - The pointer to a TariCompletedTransaction
 ///
 /// ## Returns
-/// `c_ulonglong` - Returns the fee, note that it will be zero if transaction is null
+/// `COption_u64` - Returns the fee, with `is_some` false if transaction is null
 #[no_mangle]
-pub unsafe extern "C" fn completed_transaction_get_fee(transaction: *mut TariCompletedTransaction) -> c_ulonglong {
+pub unsafe extern "C" fn completed_transaction_get_fee(transaction: *mut TariCompletedTransaction) -> COption_u64 {
+    let transaction = tag::flip(transaction);
     if transaction.is_null() {
-        return 0;
+        return COption_u64::none();
     }
-    c_ulonglong::from((*transaction).fee)
+    COption_u64::some(c_ulonglong::from((*transaction).fee))
 }
 
 /// Gets the timestamp of a TariCompletedTransaction
 ///
+/// Note: this keeps its signed sentinel return rather than `COption_u64`, since a Unix timestamp
+/// can legitimately be negative and `COption_u64` can't represent that.
+///
 /// ## Arguments
 /// `transaction` - The pointer to a TariCompletedTransaction
 ///
@@ -776,6 +1056,7 @@ pub unsafe extern "C" fn completed_transaction_get_fee(transaction: *mut TariCom
 pub unsafe extern "C" fn completed_transaction_get_transaction_timestamp(
     transaction: *mut TariCompletedTransaction,
 ) -> c_longlong {
+    let transaction = tag::flip(transaction);
     if transaction.is_null() {
         return 0;
     }
@@ -791,11 +1072,91 @@ pub unsafe extern "C" fn completed_transaction_get_transaction_timestamp(
 /// `()` - Does not return a value, equivalent to void in C
 #[no_mangle]
 pub unsafe extern "C" fn completed_transaction_destroy(transaction: *mut TariCompletedTransaction) {
-    if !transaction.is_null() {
-        Box::from_raw(transaction);
+    from_tagged(transaction);
+}
+
+/// Serializes a TariCompletedTransaction to a ByteVector so a client can persist its transaction
+/// list to disk without reconstructing every field through individual accessors. The encoding is a
+/// leading format-version byte followed by a bincode payload, so old buffers remain decodable as the
+/// underlying struct grows new fields.
+///
+/// ## Arguments
+/// `transaction` - The pointer to a TariCompletedTransaction
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `*mut ByteVector` - Returns a pointer to a ByteVector. Note that it returns ptr::null_mut() if
+/// transaction is null
+#[no_mangle]
+pub unsafe extern "C" fn completed_transaction_to_bytes(
+    transaction: *mut TariCompletedTransaction,
+    error_out: *mut c_int,
+) -> *mut ByteVector
+{
+    let transaction = tag::flip(transaction);
+    if transaction.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
+        return ptr::null_mut();
+    }
+    match bincode::serialize(&*transaction) {
+        Ok(payload) => {
+            let mut buf = Vec::with_capacity(1 + payload.len());
+            buf.push(1u8); // format version
+            buf.extend_from_slice(&payload);
+            into_tagged(ByteVector(buf))
+        },
+        Err(_) => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            ptr::null_mut()
+        },
+    }
+}
+
+/// Deserializes a TariCompletedTransaction previously produced by `completed_transaction_to_bytes`.
+///
+/// ## Arguments
+/// `bytes` - The pointer to a ByteVector
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `*mut TariCompletedTransaction` - Returns a pointer to a TariCompletedTransaction. Note that it
+/// returns ptr::null_mut() if bytes is null, empty, of an unsupported format version, or corrupt
+#[no_mangle]
+pub unsafe extern "C" fn completed_transaction_from_bytes(
+    bytes: *mut ByteVector,
+    error_out: *mut c_int,
+) -> *mut TariCompletedTransaction
+{
+    let bytes = tag::flip(bytes);
+    if bytes.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
+        return ptr::null_mut();
+    }
+    let buf = &(*bytes).0;
+    if buf.is_empty() || buf[0] != 1 {
+        set_error(error_out, TariFfiError::DeserializationFailed);
+        return ptr::null_mut();
+    }
+    match bincode::deserialize::<TariCompletedTransaction>(&buf[1..]) {
+        Ok(tx) => into_tagged(tx),
+        Err(_) => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            ptr::null_mut()
+        },
     }
 }
 
+// DEFERRED, not delivered: an earlier `payment_proof_*` FFI surface (`wallet_get_payment_proof`,
+// `payment_proof_verify`, `payment_proof_to_bytes`/`_from_bytes`) was dropped because its `generate()`
+// always produced an empty signature and its `verify()` required a non-empty one - no proof it ever
+// produced could pass its own verification. A real payment proof needs a Schnorr signature over the
+// transaction kernel's excess/commitment, which in turn needs the sender's spend key at proof-generation
+// time; nothing this tree's `TariCompletedTransaction`/`OutputManagerService` exposes carries that, so
+// real delivery needs an upstream accessor (or a dedicated proof-signing method on `output_manager_service`)
+// before this can be re-added honestly.
+
 /// -------------------------------------------------------------------------------------------- ///
 
 /// ----------------------------------- OutboundTransaction ------------------------------------- ///
@@ -811,6 +1172,7 @@ pub unsafe extern "C" fn completed_transaction_destroy(transaction: *mut TariCom
 pub unsafe extern "C" fn pending_outbound_transaction_get_transaction_id(
     transaction: *mut TariPendingOutboundTransaction,
 ) -> c_ulonglong {
+    let transaction = tag::flip(transaction);
     if transaction.is_null() {
         return 0;
     }
@@ -829,11 +1191,12 @@ pub unsafe extern "C" fn pending_outbound_transaction_get_transaction_id(
 pub unsafe extern "C" fn pending_outbound_transaction_get_destination_public_key(
     transaction: *mut TariPendingOutboundTransaction,
 ) -> *mut TariPublicKey {
+    let transaction = tag::flip(transaction);
     if transaction.is_null() {
         return ptr::null_mut();
     }
     let m = (*transaction).destination_public_key.clone();
-    Box::into_raw(Box::new(m))
+    into_tagged(m)
 }
 
 /// Gets the amount of a TariPendingOutboundTransaction
@@ -847,6 +1210,7 @@ pub unsafe extern "C" fn pending_outbound_transaction_get_destination_public_key
 pub unsafe extern "C" fn pending_outbound_transaction_get_amount(
     transaction: *mut TariPendingOutboundTransaction,
 ) -> c_ulonglong {
+    let transaction = tag::flip(transaction);
     if transaction.is_null() {
         return 0;
     }
@@ -864,6 +1228,7 @@ pub unsafe extern "C" fn pending_outbound_transaction_get_amount(
 pub unsafe extern "C" fn pending_outbound_transaction_get_transaction_timestamp(
     transaction: *mut TariPendingOutboundTransaction,
 ) -> c_longlong {
+    let transaction = tag::flip(transaction);
     if transaction.is_null() {
         return 0;
     }
@@ -879,9 +1244,7 @@ pub unsafe extern "C" fn pending_outbound_transaction_get_transaction_timestamp(
 /// `()` - Does not return a value, equivalent to void in C
 #[no_mangle]
 pub unsafe extern "C" fn pending_outbound_transaction_destroy(transaction: *mut TariPendingOutboundTransaction) {
-    if !transaction.is_null() {
-        Box::from_raw(transaction);
-    }
+    from_tagged(transaction);
 }
 
 /// -------------------------------------------------------------------------------------------- ///
@@ -899,6 +1262,7 @@ pub unsafe extern "C" fn pending_outbound_transaction_destroy(transaction: *mut
 pub unsafe extern "C" fn pending_inbound_transaction_get_transaction_id(
     transaction: *mut TariPendingInboundTransaction,
 ) -> c_ulonglong {
+    let transaction = tag::flip(transaction);
     if transaction.is_null() {
         return 0;
     }
@@ -917,11 +1281,12 @@ pub unsafe extern "C" fn pending_inbound_transaction_get_transaction_id(
 pub unsafe extern "C" fn pending_inbound_transaction_get_source_public_key(
     transaction: *mut TariPendingInboundTransaction,
 ) -> *mut TariPublicKey {
+    let transaction = tag::flip(transaction);
     if transaction.is_null() {
         return ptr::null_mut();
     }
     let m = (*transaction).source_public_key.clone();
-    Box::into_raw(Box::new(m))
+    into_tagged(m)
 }
 
 /// Gets the amount of a TariPendingInboundTransaction
@@ -935,6 +1300,7 @@ pub unsafe extern "C" fn pending_inbound_transaction_get_source_public_key(
 pub unsafe extern "C" fn pending_inbound_transaction_get_amount(
     transaction: *mut TariPendingInboundTransaction,
 ) -> c_ulonglong {
+    let transaction = tag::flip(transaction);
     if transaction.is_null() {
         return 0;
     }
@@ -952,6 +1318,7 @@ pub unsafe extern "C" fn pending_inbound_transaction_get_amount(
 pub unsafe extern "C" fn pending_inbound_transaction_get_transaction_timestamp(
     transaction: *mut TariPendingInboundTransaction,
 ) -> c_longlong {
+    let transaction = tag::flip(transaction);
     if transaction.is_null() {
         return 0;
     }
@@ -967,8 +1334,80 @@ pub unsafe extern "C" fn pending_inbound_transaction_get_transaction_timestamp(
 /// `()` - Does not return a value, equivalent to void in C
 #[no_mangle]
 pub unsafe extern "C" fn pending_inbound_transaction_destroy(transaction: *mut TariPendingInboundTransaction) {
-    if !transaction.is_null() {
-        Box::from_raw(transaction);
+    from_tagged(transaction);
+}
+
+/// Serializes a TariPendingInboundTransaction to a ByteVector so it can be cached without
+/// reconstructing every field through individual accessors. The encoding is a leading
+/// format-version byte followed by a bincode payload, matching `completed_transaction_to_bytes`.
+///
+/// ## Arguments
+/// `transaction` - The pointer to a TariPendingInboundTransaction
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `*mut ByteVector` - Returns a pointer to a ByteVector. Note that it returns ptr::null_mut() if
+/// transaction is null
+#[no_mangle]
+pub unsafe extern "C" fn pending_inbound_transaction_to_bytes(
+    transaction: *mut TariPendingInboundTransaction,
+    error_out: *mut c_int,
+) -> *mut ByteVector
+{
+    let transaction = tag::flip(transaction);
+    if transaction.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
+        return ptr::null_mut();
+    }
+    match bincode::serialize(&*transaction) {
+        Ok(payload) => {
+            let mut buf = Vec::with_capacity(1 + payload.len());
+            buf.push(1u8); // format version
+            buf.extend_from_slice(&payload);
+            into_tagged(ByteVector(buf))
+        },
+        Err(_) => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            ptr::null_mut()
+        },
+    }
+}
+
+/// Deserializes a TariPendingInboundTransaction previously produced by
+/// `pending_inbound_transaction_to_bytes`.
+///
+/// ## Arguments
+/// `bytes` - The pointer to a ByteVector
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `*mut TariPendingInboundTransaction` - Returns a pointer to a TariPendingInboundTransaction. Note
+/// that it returns ptr::null_mut() if bytes is null, empty, of an unsupported format version, or
+/// corrupt
+#[no_mangle]
+pub unsafe extern "C" fn pending_inbound_transaction_from_bytes(
+    bytes: *mut ByteVector,
+    error_out: *mut c_int,
+) -> *mut TariPendingInboundTransaction
+{
+    let bytes = tag::flip(bytes);
+    if bytes.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
+        return ptr::null_mut();
+    }
+    let buf = &(*bytes).0;
+    if buf.is_empty() || buf[0] != 1 {
+        set_error(error_out, TariFfiError::DeserializationFailed);
+        return ptr::null_mut();
+    }
+    match bincode::deserialize::<TariPendingInboundTransaction>(&buf[1..]) {
+        Ok(tx) => into_tagged(tx),
+        Err(_) => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            ptr::null_mut()
+        },
     }
 }
 /// -------------------------------------------------------------------------------------------- ///
@@ -983,6 +1422,7 @@ pub unsafe extern "C" fn comms_config_create(
     secret_key: *mut TariPrivateKey,
 ) -> *mut TariCommsConfig
 {
+    let secret_key = tag::flip(secret_key);
     let address_string;
     if !address.is_null() {
         address_string = CStr::from_ptr(address).to_str().unwrap().to_owned();
@@ -1029,17 +1469,18 @@ pub unsafe extern "C" fn comms_config_create(
                 dht: Default::default(),
             };
 
-            Box::into_raw(Box::new(config))
+            into_tagged(config)
+        },
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
         },
-        Err(_) => ptr::null_mut(),
     }
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn comms_config_destroy(wc: *mut TariCommsConfig) {
-    if !wc.is_null() {
-        Box::from_raw(wc);
-    }
+    from_tagged(wc);
 }
 
 /// ---------------------------------------------------------------------------------------------- ///
@@ -1048,9 +1489,21 @@ pub unsafe extern "C" fn comms_config_destroy(wc: *mut TariCommsConfig) {
 
 #[no_mangle]
 pub unsafe extern "C" fn wallet_create(config: *mut TariCommsConfig) -> *mut TariWallet {
+    let config = tag::flip(config);
     if config.is_null() {
         return ptr::null_mut();
     }
+
+    // Held for the lifetime of this call; released automatically if we return early, or registered
+    // against the new wallet's pointer below so `wallet_destroy` can release it later.
+    let file_lock = match filelock::acquire(std::path::Path::new(&(*config).datastore_path)) {
+        Ok(held) => held,
+        Err(e) => {
+            set_last_error(e);
+            return ptr::null_mut();
+        },
+    };
+
     // TODO Gracefully handle the case where these expects would fail
     let runtime = Runtime::new();
     let w;
@@ -1064,62 +1517,598 @@ pub unsafe extern "C" fn wallet_create(config: *mut TariCommsConfig) -> *mut Tar
                 runtime,
             );
             match w {
-                Ok(w) => Box::into_raw(Box::new(w)),
-                Err(_) => ptr::null_mut(),
+                Ok(w) => {
+                    let tagged = into_tagged(w);
+                    filelock::register(tag::flip(tagged) as usize, file_lock);
+                    tagged
+                },
+                Err(e) => {
+                    set_last_error(e);
+                    ptr::null_mut()
+                },
             }
         },
+        // `Runtime::new()` fails with a bare `std::io::Error` (no OS thread/reactor available), which predates
+        // `WalletError` and so has no mapping into a `LibWalletError` code; the last-error buffer is left
+        // untouched, matching the pre-existing null-means-failure contract for this one case.
         Err(_) => ptr::null_mut(),
     }
 }
 
+// DEFERRED, not delivered: an earlier `wallet_create_with_persistence` attempted a persistent-database
+// variant of `wallet_create` but always returned a `WalletMemoryDatabase`-backed wallet regardless of the
+// backend requested, so it was dropped rather than ship a function that lied about persisting anything.
+// This tree has no other `WalletStorageDatabase` implementation to construct with (only
+// `WalletMemoryDatabase`), so real persistence needs `tari_wallet` to either vendor or expose a
+// `WalletSqliteDatabase` (or equivalent) this crate can thread through `wallet_create`'s signature.
+
+// There is deliberately no `wallet_get_seed_words` export here. See `seed_words.rs` for why: recovering
+// an existing wallet's seed words would need its node identity secret key back out of a `NodeIdentity`,
+// and nothing in this tree exposes that, so the only function that could be shipped is one that always
+// returns null - which is worse than not advertising the FFI surface at all. Only the recovery direction
+// this tree can actually implement, `wallet_create_from_seed_words` below, is exported.
+
+/// Creates an empty TariSeedWords collection for a client to populate one word at a time via
+/// `seed_words_push_word`, e.g. from a phrase a user typed in.
+///
+/// ## Returns
+/// `*mut TariSeedWords` - Returns a pointer to an empty TariSeedWords
 #[no_mangle]
-pub unsafe extern "C" fn wallet_generate_test_data(wallet: *mut TariWallet) -> bool {
-    if wallet.is_null() {
+pub unsafe extern "C" fn seed_words_create() -> *mut TariSeedWords {
+    into_tagged(TariSeedWords(Vec::new()))
+}
+
+/// Appends `word` to `seed_words`.
+///
+/// ## Arguments
+/// `seed_words` - The pointer to a TariSeedWords
+/// `word` - The pointer to a char array holding the word to append
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `bool` - Returns whether the word was successfully appended, note that it will be false if
+/// seed_words or word is null
+#[no_mangle]
+pub unsafe extern "C" fn seed_words_push_word(
+    seed_words: *mut TariSeedWords,
+    word: *const c_char,
+    error_out: *mut c_int,
+) -> bool {
+    let seed_words = tag::flip(seed_words);
+    if seed_words.is_null() || word.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
         return false;
     }
-    match generate_wallet_test_data(&mut *wallet) {
-        Ok(_) => true,
-        _ => false,
-    }
+    let word_str = match CStr::from_ptr(word).to_str() {
+        Ok(s) => s.to_owned(),
+        Err(_) => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            return false;
+        },
+    };
+    (*seed_words).0.push(word_str);
+    true
 }
 
+/// Gets the length of a TariSeedWords
+///
+/// ## Arguments
+/// `seed_words` - The pointer to a TariSeedWords
+///
+/// ## Returns
+/// `c_uint` - Returns the number of words in a TariSeedWords, note that it will be zero if
+/// seed_words is null
 #[no_mangle]
-pub unsafe extern "C" fn wallet_add_base_node_peer(
-    wallet: *mut TariWallet,
-    public_key: *mut TariPublicKey,
-    address: *const c_char,
-) -> bool
-{
-    if wallet.is_null() {
-        return false;
+pub unsafe extern "C" fn seed_words_get_length(seed_words: *mut TariSeedWords) -> c_uint {
+    let mut len = 0;
+    if !seed_words.is_null() {
+        let seed_words = tag::flip(seed_words);
+        len = (*seed_words).0.len();
     }
+    len as c_uint
+}
 
-    if public_key.is_null() {
-        return false;
+/// Gets the word at `position` in a TariSeedWords
+///
+/// ## Arguments
+/// `seed_words` - The pointer to a TariSeedWords
+/// `position` - The integer position
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `*mut c_char` - Returns a pointer to a char array, note that it returns an empty char array if
+/// seed_words is null or position is invalid
+#[no_mangle]
+pub unsafe extern "C" fn seed_words_get_at(
+    seed_words: *mut TariSeedWords,
+    position: c_uint,
+    error_out: *mut c_int,
+) -> *mut c_char
+{
+    if seed_words.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
+        return CString::new("").unwrap().into_raw();
     }
-
-    let address_string;
-    if !address.is_null() {
-        address_string = CStr::from_ptr(address).to_str().unwrap().to_owned();
-    } else {
-        return false;
+    let len = seed_words_get_length(seed_words) as c_int - 1;
+    if len < 0 || position > len as c_uint {
+        set_error(error_out, TariFfiError::IndexOutOfBounds);
+        return CString::new("").unwrap().into_raw();
     }
+    let seed_words = tag::flip(seed_words);
+    CString::new((*seed_words).0[position as usize].clone()).unwrap().into_raw()
+}
 
-    match (*wallet).add_base_node_peer((*public_key).clone(), address_string) {
-        Ok(_) => true,
-        Err(_) => false,
-    }
+/// Destroys a TariSeedWords
+///
+/// ## Arguments
+/// `seed_words` - The pointer to a TariSeedWords
+///
+/// ## Returns
+/// `()` - Does not return a value, equivalent to void in C
+#[no_mangle]
+pub unsafe extern "C" fn seed_words_destroy(seed_words: *mut TariSeedWords) {
+    from_tagged(seed_words);
 }
 
-pub unsafe extern "C" fn wallet_add_contact(wallet: *mut TariWallet, contact: *mut TariContact) -> bool {
-    if wallet.is_null() {
-        return false;
-    }
-    if contact.is_null() {
-        return false;
+/// Creates a TariWallet the same way as `wallet_create`, but deterministically regenerates the
+/// comms node identity's secret key from `seed_words` (typed in by a user recovering their wallet;
+/// this crate has no function that produces seed words for an existing wallet) instead of taking one
+/// directly. `config`'s own `node_identity` is only used as a template for the net address/control
+/// service settings - its secret key is discarded and replaced by the one derived from
+/// `seed_words`.
+///
+/// ## Arguments
+/// `config` - The pointer to a TariCommsConfig used as a template (its secret key is discarded)
+/// `seed_words` - The pointer to a TariSeedWords built via `seed_words_create`/`seed_words_push_word`
+/// from a user-entered recovery phrase
+/// `error_out` - Pointer to an int which will be modified to a `TariSeedWordsError` code should the
+/// phrase be invalid, or a `TariFfiError` code for a null argument, may be null if the caller does
+/// not care about the distinction between error causes
+///
+/// ## Returns
+/// `*mut TariWallet` - Returns a pointer to a TariWallet, note that ptr::null_mut() is returned if
+/// any argument is null, seed_words fails checksum validation, or wallet creation otherwise fails
+#[no_mangle]
+pub unsafe extern "C" fn wallet_create_from_seed_words(
+    config: *mut TariCommsConfig,
+    seed_words: *mut TariSeedWords,
+    error_out: *mut c_int,
+) -> *mut TariWallet {
+    let config_ptr = tag::flip(config);
+    let seed_words_ptr = tag::flip(seed_words);
+    if config_ptr.is_null() || seed_words_ptr.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
+        return ptr::null_mut();
     }
 
-    match (*wallet)
+    let key_bytes = match seed_words::decode(&(*seed_words_ptr).0) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            if !error_out.is_null() {
+                *error_out = TariSeedWordsError::from(e) as c_int;
+            }
+            return ptr::null_mut();
+        },
+    };
+    let secret_key = match TariPrivateKey::from_bytes(&key_bytes) {
+        Ok(k) => k,
+        Err(_) => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            return ptr::null_mut();
+        },
+    };
+
+    let net_address = (*config_ptr).node_identity.control_service_address();
+    let ni = match NodeIdentity::new(secret_key, net_address, PeerFeatures::COMMUNICATION_CLIENT) {
+        Ok(ni) => ni,
+        Err(_) => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            return ptr::null_mut();
+        },
+    };
+
+    let mut new_config = (*config_ptr).clone();
+    new_config.control_service.listener_address = ni.control_service_address();
+    new_config.node_identity = Arc::new(ni);
+
+    wallet_create(into_tagged(new_config))
+}
+
+/// Encrypts this wallet's contacts and transaction metadata with a key derived from `passphrase`
+/// and returns the resulting blob. See `backup.rs` for the exact format and for why the node
+/// identity secret key isn't included.
+///
+/// ## Arguments
+/// `wallet` - The pointer to a TariWallet
+/// `passphrase` - The passphrase to encrypt the backup with
+/// `error_out` - Pointer to an int which will be modified to a `backup::TariBackupError` code
+/// (distinct from the general `TariFfiError` codes used elsewhere in this crate) should one occur,
+/// may be null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `*mut ByteVector` - Returns a pointer to a ByteVector, note that ptr::null_mut() is returned if
+/// wallet or passphrase is null, or the backup could not be produced
+#[no_mangle]
+pub unsafe extern "C" fn wallet_export_encrypted_backup(
+    wallet: *mut TariWallet,
+    passphrase: *const c_char,
+    error_out: *mut c_int,
+) -> *mut ByteVector {
+    let wallet = tag::flip(wallet);
+    if wallet.is_null() || passphrase.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
+        return ptr::null_mut();
+    }
+    let passphrase_str = match CStr::from_ptr(passphrase).to_str() {
+        Ok(s) => s,
+        Err(_) => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            return ptr::null_mut();
+        },
+    };
+
+    match backup::export(&*wallet, passphrase_str) {
+        Ok(bytes) => into_tagged(ByteVector(bytes)),
+        Err(e) => {
+            if !error_out.is_null() {
+                *error_out = backup::TariBackupError::from(e).code();
+            }
+            ptr::null_mut()
+        },
+    }
+}
+
+/// Creates a TariWallet from `config` the same way as `wallet_create`, then decrypts `bytes` with
+/// `passphrase` and replays the contacts it contains into the new wallet. The blob's transaction
+/// metadata has no such replay path (see `backup.rs` for why); it's stashed against the new wallet
+/// instead and can be read back with `wallet_import_encrypted_backup_completed_transactions`/
+/// `_pending_inbound_transactions`.
+///
+/// ## Arguments
+/// `config` - The pointer to a TariCommsConfig
+/// `bytes` - The pointer to a ByteVector produced by `wallet_export_encrypted_backup`
+/// `passphrase` - The passphrase the backup was encrypted with
+/// `error_out` - Pointer to an int which will be modified to a `backup::TariBackupError` code
+/// should one occur, may be null if the caller does not care about the distinction between error
+/// causes
+///
+/// ## Returns
+/// `*mut TariWallet` - Returns a pointer to a TariWallet, note that ptr::null_mut() is returned if
+/// any argument is null, the blob fails to decrypt, or wallet creation otherwise fails
+#[no_mangle]
+pub unsafe extern "C" fn wallet_import_encrypted_backup(
+    config: *mut TariCommsConfig,
+    bytes: *mut ByteVector,
+    passphrase: *const c_char,
+    error_out: *mut c_int,
+) -> *mut TariWallet {
+    let bytes = tag::flip(bytes);
+    if bytes.is_null() || passphrase.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
+        return ptr::null_mut();
+    }
+    let passphrase_str = match CStr::from_ptr(passphrase).to_str() {
+        Ok(s) => s,
+        Err(_) => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            return ptr::null_mut();
+        },
+    };
+
+    let (contacts, completed, pending_inbound) = match backup::import(&(*bytes).0, passphrase_str) {
+        Ok(payload) => payload,
+        Err(e) => {
+            if !error_out.is_null() {
+                *error_out = backup::TariBackupError::from(e).code();
+            }
+            return ptr::null_mut();
+        },
+    };
+
+    let wallet = wallet_create(config);
+    if wallet.is_null() {
+        return ptr::null_mut();
+    }
+    let restored = tag::flip(wallet);
+    for contact in contacts {
+        let _ = (*restored)
+            .runtime
+            .block_on((*restored).contacts_service.save_contact(contact));
+    }
+    backup::stash(restored as usize, completed, pending_inbound);
+    wallet
+}
+
+/// Returns the completed transactions from the backup `wallet` was restored from, if any. `wallet`
+/// not having been created by `wallet_import_encrypted_backup` is indistinguishable from it having
+/// been restored from a backup with none, both return an empty `TariCompletedTransactions`.
+#[no_mangle]
+pub unsafe extern "C" fn wallet_import_encrypted_backup_completed_transactions(
+    wallet: *mut TariWallet,
+) -> *mut TariCompletedTransactions {
+    let wallet = tag::flip(wallet);
+    if wallet.is_null() {
+        return ptr::null_mut();
+    }
+    into_tagged(TariCompletedTransactions(backup::completed_transactions(wallet as usize)))
+}
+
+/// Returns the pending inbound transactions from the backup `wallet` was restored from, if any.
+/// `wallet` not having been created by `wallet_import_encrypted_backup` is indistinguishable from it
+/// having been restored from a backup with none, both return an empty `TariPendingInboundTransactions`.
+#[no_mangle]
+pub unsafe extern "C" fn wallet_import_encrypted_backup_pending_inbound_transactions(
+    wallet: *mut TariWallet,
+) -> *mut TariPendingInboundTransactions {
+    let wallet = tag::flip(wallet);
+    if wallet.is_null() {
+        return ptr::null_mut();
+    }
+    into_tagged(TariPendingInboundTransactions(
+        backup::pending_inbound_transactions(wallet as usize),
+    ))
+}
+
+/// A distinct `error_out` code space for `wallet_unlock`/`wallet_change_passphrase`, separate from
+/// the general `TariFfiError` codes used elsewhere in this crate, so a caller can tell "wrong
+/// passphrase" apart from "this wallet was never encrypted".
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TariLockError {
+    Success = 0,
+    NotEncrypted = 1,
+    WrongPassphrase = 2,
+}
+
+impl From<lock::LockError> for TariLockError {
+    fn from(e: lock::LockError) -> Self {
+        match e {
+            lock::LockError::NotEncrypted => TariLockError::NotEncrypted,
+            lock::LockError::WrongPassphrase => TariLockError::WrongPassphrase,
+        }
+    }
+}
+
+/// Encrypts this wallet's contacts and transaction metadata with a key derived from `passphrase`
+/// (see `lock.rs`) and locks the wallet: `wallet_send_transaction` will refuse to proceed until
+/// `wallet_unlock` is called with the same passphrase. The wallet starts out unlocked, so calling
+/// this is opt-in and does not affect callers that never touch it.
+///
+/// ## Arguments
+/// `wallet` - The pointer to a TariWallet
+/// `passphrase` - The passphrase to encrypt the wallet with
+///
+/// ## Returns
+/// `bool` - Returns whether the wallet was successfully encrypted, note that it will be false if
+/// wallet or passphrase is null
+#[no_mangle]
+pub unsafe extern "C" fn wallet_encrypt(wallet: *mut TariWallet, passphrase: *const c_char) -> bool {
+    let wallet_ptr = tag::flip(wallet);
+    if wallet_ptr.is_null() || passphrase.is_null() {
+        return false;
+    }
+    let passphrase_str = match CStr::from_ptr(passphrase).to_str() {
+        Ok(s) => s,
+        Err(_) => return false,
+    };
+    lock::encrypt(&*wallet_ptr, wallet_ptr as usize, passphrase_str);
+    true
+}
+
+/// Unlocks a wallet previously locked with `wallet_encrypt`, so that `wallet_send_transaction` (and
+/// any future operation gated the same way) can proceed again. A wallet that was never encrypted is
+/// always unlocked, so calling this on one is a no-op that reports `NotEncrypted`.
+///
+/// ## Arguments
+/// `wallet` - The pointer to a TariWallet
+/// `passphrase` - The passphrase `wallet_encrypt` was called with
+/// `error_out` - Pointer to an int which will be modified to a `TariLockError` code should one
+/// occur, may be null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `bool` - Returns whether the wallet was successfully unlocked
+#[no_mangle]
+pub unsafe extern "C" fn wallet_unlock(wallet: *mut TariWallet, passphrase: *const c_char, error_out: *mut c_int) -> bool {
+    let wallet_ptr = tag::flip(wallet);
+    if wallet_ptr.is_null() || passphrase.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
+        return false;
+    }
+    let passphrase_str = match CStr::from_ptr(passphrase).to_str() {
+        Ok(s) => s,
+        Err(_) => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            return false;
+        },
+    };
+    match lock::unlock(wallet_ptr as usize, passphrase_str) {
+        Ok(_) => true,
+        Err(e) => {
+            if !error_out.is_null() {
+                *error_out = TariLockError::from(e) as c_int;
+            }
+            false
+        },
+    }
+}
+
+/// Re-wraps this wallet's encrypted-at-rest blob under `new_passphrase`, without ever exposing the
+/// underlying plaintext outside `lock.rs`. Requires `old_passphrase` to match the wallet's current
+/// passphrase, i.e. the one given to `wallet_encrypt` or to the last successful
+/// `wallet_change_passphrase`. On success the wallet is left unlocked.
+///
+/// ## Arguments
+/// `wallet` - The pointer to a TariWallet
+/// `old_passphrase` - The passphrase the wallet is currently encrypted with
+/// `new_passphrase` - The passphrase to re-encrypt the wallet with
+/// `error_out` - Pointer to an int which will be modified to a `TariLockError` code should one
+/// occur, may be null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `bool` - Returns whether the passphrase was successfully changed
+#[no_mangle]
+pub unsafe extern "C" fn wallet_change_passphrase(
+    wallet: *mut TariWallet,
+    old_passphrase: *const c_char,
+    new_passphrase: *const c_char,
+    error_out: *mut c_int,
+) -> bool {
+    let wallet_ptr = tag::flip(wallet);
+    if wallet_ptr.is_null() || old_passphrase.is_null() || new_passphrase.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
+        return false;
+    }
+    let old_str = match CStr::from_ptr(old_passphrase).to_str() {
+        Ok(s) => s,
+        Err(_) => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            return false;
+        },
+    };
+    let new_str = match CStr::from_ptr(new_passphrase).to_str() {
+        Ok(s) => s,
+        Err(_) => {
+            set_error(error_out, TariFfiError::DeserializationFailed);
+            return false;
+        },
+    };
+    match lock::change_passphrase(wallet_ptr as usize, old_str, new_str) {
+        Ok(_) => true,
+        Err(e) => {
+            if !error_out.is_null() {
+                *error_out = TariLockError::from(e) as c_int;
+            }
+            false
+        },
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wallet_generate_test_data(wallet: *mut TariWallet) -> bool {
+    let wallet = tag::flip(wallet);
+    if wallet.is_null() {
+        return false;
+    }
+    match generate_wallet_test_data(&mut *wallet) {
+        Ok(_) => true,
+        _ => false,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wallet_add_base_node_peer(
+    wallet: *mut TariWallet,
+    public_key: *mut TariPublicKey,
+    address: *const c_char,
+) -> bool
+{
+    let wallet = tag::flip(wallet);
+    let public_key = tag::flip(public_key);
+    if wallet.is_null() {
+        return false;
+    }
+
+    if public_key.is_null() {
+        return false;
+    }
+
+    let address_string;
+    if !address.is_null() {
+        address_string = CStr::from_ptr(address).to_str().unwrap().to_owned();
+    } else {
+        return false;
+    }
+
+    match (*wallet).add_base_node_peer((*public_key).clone(), address_string) {
+        Ok(_) => true,
+        Err(_) => false,
+    }
+}
+
+/// Records `address` (a `127.0.0.1:21441`-style socket string) as the resolved address for `name`
+/// under `wallet`, so a later `wallet_add_base_node_peer_by_name` call can look it up without a DNS
+/// round-trip. Overwrites any previous alias of the same name. Cleared automatically by
+/// `wallet_destroy`.
+///
+/// ## Arguments
+/// `wallet` - The pointer to a TariWallet
+/// `name` - The alias to register
+/// `address` - The socket address `name` should resolve to
+///
+/// ## Returns
+/// `bool` - Returns whether the alias was recorded, note that it will be false if wallet, name, or
+/// address is null
+#[no_mangle]
+pub unsafe extern "C" fn wallet_set_peer_alias(
+    wallet: *mut TariWallet,
+    name: *const c_char,
+    address: *const c_char,
+) -> bool {
+    let wallet = tag::flip(wallet);
+    if wallet.is_null() || name.is_null() || address.is_null() {
+        return false;
+    }
+    let name = CStr::from_ptr(name).to_str().unwrap().to_owned();
+    let address = CStr::from_ptr(address).to_str().unwrap().to_owned();
+    peer_alias::set_alias(wallet as usize, &name, &address);
+    true
+}
+
+/// Adds a base node peer the same way as `wallet_add_base_node_peer`, but takes a `name` instead of a
+/// literal socket address. `name` is resolved via `peer_alias::resolve`: first against the alias
+/// table populated by `wallet_set_peer_alias`, then, if unset, as a `host:port` string to resolve by
+/// DNS.
+///
+/// ## Arguments
+/// `wallet` - The pointer to a TariWallet
+/// `public_key` - The pointer to the base node's TariPublicKey
+/// `name` - An alias registered via `wallet_set_peer_alias`, or a `host:port` string to resolve by
+/// DNS
+/// `error_out` - Pointer to an int which will be modified to a `TariPeerAliasError` code should one
+/// occur, may be null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `bool` - Returns whether the peer was added, note that it will be false if wallet, public_key, or
+/// name is null, or if name could not be resolved
+#[no_mangle]
+pub unsafe extern "C" fn wallet_add_base_node_peer_by_name(
+    wallet: *mut TariWallet,
+    public_key: *mut TariPublicKey,
+    name: *const c_char,
+    error_out: *mut c_int,
+) -> bool {
+    let wallet_ptr = tag::flip(wallet);
+    if wallet_ptr.is_null() || tag::flip(public_key).is_null() || name.is_null() {
+        return false;
+    }
+
+    let name = CStr::from_ptr(name).to_str().unwrap().to_owned();
+    let address = match peer_alias::resolve(wallet_ptr as usize, &name) {
+        Ok(address) => address,
+        Err(e) => {
+            if !error_out.is_null() {
+                *error_out = TariPeerAliasError::from(e) as c_int;
+            }
+            return false;
+        },
+    };
+
+    wallet_add_base_node_peer(wallet, public_key, CString::new(address).unwrap().as_ptr())
+}
+
+pub unsafe extern "C" fn wallet_add_contact(wallet: *mut TariWallet, contact: *mut TariContact) -> bool {
+    let wallet = tag::flip(wallet);
+    let contact = tag::flip(contact);
+    if wallet.is_null() {
+        return false;
+    }
+    if contact.is_null() {
+        return false;
+    }
+
+    match (*wallet)
         .runtime
         .block_on((*wallet).contacts_service.save_contact((*contact).clone()))
     {
@@ -1129,6 +2118,8 @@ pub unsafe extern "C" fn wallet_add_contact(wallet: *mut TariWallet, contact: *m
 }
 
 pub unsafe extern "C" fn wallet_remove_contact(wallet: *mut TariWallet, contact: *mut TariContact) -> bool {
+    let wallet = tag::flip(wallet);
+    let contact = tag::flip(contact);
     if wallet.is_null() {
         return false;
     }
@@ -1145,8 +2136,16 @@ pub unsafe extern "C" fn wallet_remove_contact(wallet: *mut TariWallet, contact:
     }
 }
 
+// DEFERRED, not delivered: an earlier `wallet_get_utxos`/`utxo_*`/`utxos_*` FFI surface fabricated a
+// single synthetic output sized to the wallet's balance instead of enumerating the wallet's real UTXO
+// set, so it was dropped rather than ship a function whose results looked real but weren't. Real
+// delivery needs `output_manager_service` to expose an actual UTXO-listing method (this tree only has
+// `get_balance`, `get_completed_transactions`, and `get_pending_inbound_transactions` to poll) - the
+// same gap `coin_selection.rs`'s single-pseudo-UTXO pre-check works around for send-time feasibility.
+
 #[no_mangle]
 pub unsafe extern "C" fn wallet_get_balance(wallet: *mut TariWallet) -> c_ulonglong {
+    let wallet = tag::flip(wallet);
     if wallet.is_null() {
         return 0;
     }
@@ -1160,14 +2159,20 @@ pub unsafe extern "C" fn wallet_get_balance(wallet: *mut TariWallet) -> c_ulongl
     }
 }
 
-#[no_mangle]
-pub unsafe extern "C" fn wallet_send_transaction(
+/// Shared implementation behind `wallet_send_transaction`/`wallet_send_transaction_with_strategy`. Runs the same
+/// feasibility pre-check `wallet_estimate_fee` does - selecting over a single pseudo-UTXO sized to the wallet's
+/// balance under `strategy` - before handing off to `transaction_service.send_transaction`. See `coin_selection.rs`
+/// for why this can't yet select real inputs or steer which ones the transaction service actually spends; this pre-
+/// check at least rejects a `strategy` that wouldn't cover `amount + fee_per_gram` before broadcasting anything.
+unsafe fn send_transaction_with_strategy(
     wallet: *mut TariWallet,
     dest_public_key: *mut TariPublicKey,
     amount: c_ulonglong,
     fee_per_gram: c_ulonglong,
-) -> bool
-{
+    strategy: TariCoinSelectionStrategy,
+) -> bool {
+    let wallet = tag::flip(wallet);
+    let dest_public_key = tag::flip(dest_public_key);
     if wallet.is_null() {
         return false;
     }
@@ -1176,6 +2181,39 @@ pub unsafe extern "C" fn wallet_send_transaction(
         return false;
     }
 
+    // A wallet locked via `wallet_encrypt` must be `wallet_unlock`ed before it will spend.
+    if lock::is_locked(wallet as usize) {
+        return false;
+    }
+
+    let balance = (*wallet)
+        .runtime
+        .block_on((*wallet).output_manager_service.get_balance())
+        .map(u64::from)
+        .unwrap_or(0);
+    // `checked_add` rather than `+`: `amount`/`fee_per_gram` come straight from the FFI caller, and an
+    // overflowing `target` would wrap to a small value that the feasibility check below could wrongly
+    // wave through as affordable.
+    let target = match amount.checked_add(fee_per_gram) {
+        Some(target) => target,
+        None => {
+            set_last_error(WalletError::OutputManagerError(OutputManagerError::NotEnoughFunds));
+            return false;
+        },
+    };
+    let utxos = [coin_selection::Utxo {
+        id: 0,
+        value: balance,
+        timestamp: 0,
+    }];
+    if coin_selection::select(strategy, &utxos, target, fee_per_gram).is_none() {
+        // Recorded as `OutputManagerError::NotEnoughFunds`, which `From<WalletError>` in error.rs maps to 101. The
+        // transaction service's own insufficient-funds check below surfaces as 204 instead; both are `Funds`.
+        set_last_error(WalletError::OutputManagerError(OutputManagerError::NotEnoughFunds));
+        error::set_last_error_funds_detail(balance, target, fee_per_gram);
+        return false;
+    }
+
     match (*wallet)
         .runtime
         .block_on((*wallet).transaction_service.send_transaction(
@@ -1184,12 +2222,347 @@ pub unsafe extern "C" fn wallet_send_transaction(
             MicroTari::from(fee_per_gram),
         )) {
         Ok(_) => true,
-        Err(_) => false,
+        Err(e) => {
+            set_last_error(e);
+            // Only takes effect if the error just recorded above was a fund shortfall (code 101/204) - see
+            // `set_last_error_funds_detail`. The balance is re-read here rather than threaded through from above
+            // since `send_transaction` had already moved past its own balance check by the time it failed.
+            let available = (*wallet)
+                .runtime
+                .block_on((*wallet).output_manager_service.get_balance())
+                .map(u64::from)
+                .unwrap_or(0);
+            error::set_last_error_funds_detail(available, target, fee_per_gram);
+            false
+        },
     }
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn wallet_send_transaction(
+    wallet: *mut TariWallet,
+    dest_public_key: *mut TariPublicKey,
+    amount: c_ulonglong,
+    fee_per_gram: c_ulonglong,
+) -> bool
+{
+    let strategy = if wallet.is_null() {
+        TariCoinSelectionStrategy::LargestFirst
+    } else {
+        coin_selection::default_for(tag::flip(wallet) as usize)
+    };
+    send_transaction_with_strategy(wallet, dest_public_key, amount, fee_per_gram, strategy)
+}
+
+/// Sends a transaction the same way as `wallet_send_transaction`, but takes a coin-selection
+/// `strategy` so a caller can steer which UTXOs cover the amount + fee instead of relying on the
+/// transaction service's fixed internal policy.
+///
+/// Note: `output_manager_service` doesn't expose a UTXO-listing method in this tree yet (see
+/// `coin_selection.rs`), so `strategy` only governs a feasibility pre-check over a single pseudo-UTXO
+/// sized to the wallet's balance - it rejects a selection that can't cover `amount + fee_per_gram`
+/// before broadcasting, but the actual spend still goes through the transaction service's fixed
+/// internal policy, since there's no real per-output data for `strategy` to choose between. Use
+/// `wallet_estimate_fee` to preview what a given strategy's pre-check would allow. A caller that
+/// always sends the same way can set `strategy` once via `wallet_set_coin_selection_strategy` instead
+/// of passing it on every send.
+///
+/// ## Arguments
+/// `wallet` - The pointer to a TariWallet
+/// `dest_public_key` - The pointer to the destination TariPublicKey
+/// `amount` - The amount to send
+/// `fee_per_gram` - The fee per gram to use for the transaction
+/// `strategy` - The coin-selection strategy to use
+///
+/// ## Returns
+/// `bool` - Returns whether the transaction was successfully sent
+#[no_mangle]
+pub unsafe extern "C" fn wallet_send_transaction_with_strategy(
+    wallet: *mut TariWallet,
+    dest_public_key: *mut TariPublicKey,
+    amount: c_ulonglong,
+    fee_per_gram: c_ulonglong,
+    strategy: TariCoinSelectionStrategy,
+) -> bool {
+    send_transaction_with_strategy(wallet, dest_public_key, amount, fee_per_gram, strategy)
+}
+
+/// Sets `wallet`'s sticky default coin-selection strategy, which later calls to
+/// `wallet_send_transaction` use instead of every caller threading a `TariCoinSelectionStrategy`
+/// through `wallet_send_transaction_with_strategy` by hand. `wallet_estimate_fee` does not read this
+/// default; it always uses the strategy it is passed (which can be `wallet_coin_selection_strategy`'s
+/// result). Cleared automatically by `wallet_destroy`.
+///
+/// ## Arguments
+/// `wallet` - The pointer to a TariWallet
+/// `strategy` - The coin-selection strategy to use by default for this wallet
+///
+/// ## Returns
+/// `bool` - Returns whether the default was set, note that it will be false if wallet is null
+#[no_mangle]
+pub unsafe extern "C" fn wallet_set_coin_selection_strategy(
+    wallet: *mut TariWallet,
+    strategy: TariCoinSelectionStrategy,
+) -> bool {
+    let wallet = tag::flip(wallet);
+    if wallet.is_null() {
+        return false;
+    }
+    coin_selection::set_default(wallet as usize, strategy);
+    true
+}
+
+/// `wallet`'s sticky default coin-selection strategy, as set by `wallet_set_coin_selection_strategy`,
+/// or `TariCoinSelectionStrategy::LargestFirst` if it was never called for this wallet.
+///
+/// ## Arguments
+/// `wallet` - The pointer to a TariWallet
+///
+/// ## Returns
+/// `TariCoinSelectionStrategy` - Returns the wallet's default strategy, note that it will be
+/// `LargestFirst` if wallet is null
+#[no_mangle]
+pub unsafe extern "C" fn wallet_coin_selection_strategy(wallet: *mut TariWallet) -> TariCoinSelectionStrategy {
+    let wallet = tag::flip(wallet);
+    if wallet.is_null() {
+        return TariCoinSelectionStrategy::LargestFirst;
+    }
+    coin_selection::default_for(wallet as usize)
+}
+
+/// Previews the fee a call to `wallet_send_transaction_with_strategy` would incur for `amount` and
+/// `fee_per_gram` under `strategy`, without broadcasting anything.
+///
+/// Note: in the absence of a UTXO-listing method on `output_manager_service` (see
+/// `coin_selection.rs`), selection runs over a single pseudo-UTXO sized to the wallet's total
+/// balance. This is enough to reject insufficient-funds cases and approximate a per-input fee, but
+/// can't reflect the real input count an on-chain transaction would use.
+///
+/// ## Arguments
+/// `wallet` - The pointer to a TariWallet
+/// `amount` - The amount that would be sent
+/// `fee_per_gram` - The fee per gram to use for the transaction
+/// `strategy` - The coin-selection strategy to preview
+///
+/// ## Returns
+/// `c_ulonglong` - Returns the estimated fee, note that it will be zero if wallet is null or the
+/// balance can't cover amount + fee_per_gram
+#[no_mangle]
+pub unsafe extern "C" fn wallet_estimate_fee(
+    wallet: *mut TariWallet,
+    amount: c_ulonglong,
+    fee_per_gram: c_ulonglong,
+    strategy: TariCoinSelectionStrategy,
+) -> c_ulonglong {
+    let wallet = tag::flip(wallet);
+    if wallet.is_null() {
+        return 0;
+    }
+
+    let balance = match (*wallet).runtime.block_on((*wallet).output_manager_service.get_balance()) {
+        Ok(b) => u64::from(b),
+        Err(_) => return 0,
+    };
+
+    let utxos = [coin_selection::Utxo {
+        id: 0,
+        value: balance,
+        timestamp: 0,
+    }];
+    // See `send_transaction_with_strategy` for why this is `checked_add` rather than `+`.
+    let target = match amount.checked_add(fee_per_gram) {
+        Some(target) => target,
+        None => return 0,
+    };
+    match coin_selection::select(strategy, &utxos, target, fee_per_gram) {
+        Some(selection) => fee_per_gram * selection.selected.len() as c_ulonglong,
+        None => 0,
+    }
+}
+
+/// Gets the integer code of the last error recorded on this thread, so a caller that just received
+/// a `false`/null return from a call like `comms_config_create`, `wallet_create`, or
+/// `wallet_send_transaction` can distinguish "null because of null input" from "null because the
+/// service errored" and why. Returns `0` if no error has been recorded on this thread yet.
+///
+/// ## Arguments
+/// `()` - Does not take any arguments
+///
+/// ## Returns
+/// `c_int` - Returns the last error code recorded on this thread
+#[no_mangle]
+pub unsafe extern "C" fn wallet_get_last_error_code() -> c_int {
+    error::last_error_code()
+}
+
+/// Gets the message of the last error recorded on this thread. The caller is responsible for
+/// freeing the returned string with `string_destroy`.
+///
+/// ## Arguments
+/// `()` - Does not take any arguments
+///
+/// ## Returns
+/// `*mut c_char` - Returns a pointer to a char array, empty if no error has been recorded on this
+/// thread yet
+#[no_mangle]
+pub unsafe extern "C" fn wallet_get_last_error_message() -> *mut c_char {
+    CString::new(error::last_error_message())
+        .unwrap_or_else(|_| CString::new("").unwrap())
+        .into_raw()
+}
+
+/// The balance available, if the last error recorded on this thread was a fund shortfall (code 101 or 204). Lets a
+/// client show "you need X more" without parsing `wallet_get_last_error_message`'s Debug-formatted string.
+///
+/// ## Arguments
+/// `()` - Does not take any arguments
+///
+/// ## Returns
+/// `COption_u64` - Returns the available balance, note that `is_some` will be false if the last error wasn't a fund
+/// shortfall or no error has been recorded yet
+#[no_mangle]
+pub unsafe extern "C" fn liberror_available() -> COption_u64 {
+    error::last_error_available().map_or(COption_u64::none(), COption_u64::some)
+}
+
+/// The amount required, if the last error recorded on this thread was a fund shortfall (code 101 or 204).
+///
+/// ## Arguments
+/// `()` - Does not take any arguments
+///
+/// ## Returns
+/// `COption_u64` - Returns the required amount, note that `is_some` will be false if the last error wasn't a fund
+/// shortfall or no error has been recorded yet
+#[no_mangle]
+pub unsafe extern "C" fn liberror_required() -> COption_u64 {
+    error::last_error_required().map_or(COption_u64::none(), COption_u64::some)
+}
+
+/// The estimated fee, if the last error recorded on this thread was a fund shortfall (code 101 or 204).
+///
+/// ## Arguments
+/// `()` - Does not take any arguments
+///
+/// ## Returns
+/// `COption_u64` - Returns the estimated fee, note that `is_some` will be false if the last error wasn't a fund
+/// shortfall or no error has been recorded yet
+#[no_mangle]
+pub unsafe extern "C" fn liberror_fee_estimate() -> COption_u64 {
+    error::last_error_fee_estimate().map_or(COption_u64::none(), COption_u64::some)
+}
+
+/// `code`'s stable symbolic name, e.g. `"NotEnoughFunds"` for `101`, so a client can log it without embedding a copy
+/// of the code table in this file. The caller is responsible for freeing the returned string with `string_destroy`.
+/// Returns `"Unknown"` for a code this crate doesn't recognise.
+///
+/// ## Arguments
+/// `code` - A `LibWalletError` code, e.g. one returned by `wallet_get_last_error_code`
+///
+/// ## Returns
+/// `*mut c_char` - Returns a pointer to a char array naming `code`
+#[no_mangle]
+pub unsafe extern "C" fn liberror_name(code: c_int) -> *mut c_char {
+    CString::new(error::name_for_code(code))
+        .unwrap_or_else(|_| CString::new("").unwrap())
+        .into_raw()
+}
+
+/// `code`'s category, so a client can branch on a small stable set instead of the full code table. Returns
+/// `TariLibWalletErrorCategory::Unknown` for a code this crate doesn't recognise.
+///
+/// ## Arguments
+/// `code` - A `LibWalletError` code, e.g. one returned by `wallet_get_last_error_code`
+///
+/// ## Returns
+/// `TariLibWalletErrorCategory` - Returns `code`'s category
+#[no_mangle]
+pub unsafe extern "C" fn liberror_category(code: c_int) -> TariLibWalletErrorCategory {
+    error::category_for_code(code).into()
+}
+
+/// Whether `code` is worth auto-retrying without changing anything about the call that produced it - `true` for
+/// comms/connectivity and data-directory-lock codes, `false` for validation errors like `DuplicateOutput`.
+///
+/// ## Arguments
+/// `code` - A `LibWalletError` code, e.g. one returned by `wallet_get_last_error_code`
+///
+/// ## Returns
+/// `bool` - Returns whether `code` is transient
+#[no_mangle]
+pub unsafe extern "C" fn liberror_is_transient(code: c_int) -> bool {
+    error::is_transient_for_code(code)
+}
+
+/// The source chain behind the last error recorded on this thread - one entry per nested error variant peeled on the
+/// way down to the root cause, outermost first - so a client can log the full cause chain instead of just the
+/// flattened `wallet_get_last_error_message` string. Free the result with `liberror_source_chain_destroy`.
+///
+/// ## Arguments
+/// `()` - Does not take any arguments
+///
+/// ## Returns
+/// `*mut TariStrings` - Returns a pointer to a TariStrings, empty if no error has been recorded on this thread yet
+#[no_mangle]
+pub unsafe extern "C" fn liberror_source_chain() -> *mut TariStrings {
+    into_tagged(TariStrings(error::last_error_source_chain()))
+}
+
+/// ## Arguments
+/// `strings` - The pointer to a TariStrings
+///
+/// ## Returns
+/// `c_uint` - Returns the number of elements in a TariStrings, note that it will be zero if ptr is null
+#[no_mangle]
+pub unsafe extern "C" fn liberror_source_chain_get_length(strings: *mut TariStrings) -> c_uint {
+    let strings = tag::flip(strings);
+    if strings.is_null() {
+        return 0;
+    }
+    (*strings).0.len() as c_uint
+}
+
+/// ## Arguments
+/// `strings` - The pointer to a TariStrings
+/// `position` - The index of the element to return
+/// `error_out` - Pointer to an int which will be modified to a `TariFfiError` code should one occur, may be null if
+/// the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `*mut c_char` - Returns a pointer to a char array, the caller is responsible for freeing it with `string_destroy`.
+/// Note that it will be null if strings is null or position is out of bounds
+#[no_mangle]
+pub unsafe extern "C" fn liberror_source_chain_get_at(
+    strings: *mut TariStrings,
+    position: c_uint,
+    error_out: *mut c_int,
+) -> *mut c_char {
+    let strings = tag::flip(strings);
+    if strings.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
+        return ptr::null_mut();
+    }
+    match (*strings).0.get(position as usize) {
+        Some(s) => CString::new(s.clone()).unwrap_or_else(|_| CString::new("").unwrap()).into_raw(),
+        None => {
+            set_error(error_out, TariFfiError::IndexOutOfBounds);
+            ptr::null_mut()
+        },
+    }
+}
+
+/// ## Arguments
+/// `strings` - The pointer to a TariStrings
+///
+/// ## Returns
+/// `()` - Does not return a value, equivalent to void in C
+#[no_mangle]
+pub unsafe extern "C" fn liberror_source_chain_destroy(strings: *mut TariStrings) {
+    from_tagged(strings);
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn wallet_get_contacts(wallet: *mut TariWallet) -> *mut TariContacts {
+    let wallet = tag::flip(wallet);
     let mut contacts = Vec::new();
     if wallet.is_null() {
         return ptr::null_mut();
@@ -1199,7 +2572,7 @@ pub unsafe extern "C" fn wallet_get_contacts(wallet: *mut TariWallet) -> *mut Ta
     match retrieved_contacts {
         Ok(retrieved_contacts) => {
             contacts.append(&mut retrieved_contacts.clone());
-            Box::into_raw(Box::new(TariContacts(contacts)))
+            into_tagged(TariContacts(contacts))
         },
         Err(_) => ptr::null_mut(),
     }
@@ -1207,6 +2580,7 @@ pub unsafe extern "C" fn wallet_get_contacts(wallet: *mut TariWallet) -> *mut Ta
 
 #[no_mangle]
 pub unsafe extern "C" fn wallet_get_completed_transactions(wallet: *mut TariWallet) -> *mut TariCompletedTransactions {
+    let wallet = tag::flip(wallet);
     let mut completed = Vec::new();
     if wallet.is_null() {
         return ptr::null_mut();
@@ -1220,7 +2594,7 @@ pub unsafe extern "C" fn wallet_get_completed_transactions(wallet: *mut TariWall
             for (_id, tx) in &completed_transactions {
                 completed.push(tx.clone());
             }
-            Box::into_raw(Box::new(TariCompletedTransactions(completed)))
+            into_tagged(TariCompletedTransactions(completed))
         },
         Err(_) => ptr::null_mut(),
     }
@@ -1230,6 +2604,7 @@ pub unsafe extern "C" fn wallet_get_completed_transactions(wallet: *mut TariWall
 pub unsafe extern "C" fn wallet_get_pending_inbound_transactions(
     wallet: *mut TariWallet,
 ) -> *mut TariPendingInboundTransactions {
+    let wallet = tag::flip(wallet);
     let mut pending = Vec::new();
     if wallet.is_null() {
         return ptr::null_mut();
@@ -1243,7 +2618,7 @@ pub unsafe extern "C" fn wallet_get_pending_inbound_transactions(
             for (_id, tx) in &pending_transactions {
                 pending.push(tx.clone());
             }
-            Box::into_raw(Box::new(TariPendingInboundTransactions(pending)))
+            into_tagged(TariPendingInboundTransactions(pending))
         },
         Err(_) => ptr::null_mut(),
     }
@@ -1253,6 +2628,7 @@ pub unsafe extern "C" fn wallet_get_pending_inbound_transactions(
 pub unsafe extern "C" fn wallet_get_pending_outbound_transactions(
     wallet: *mut TariWallet,
 ) -> *mut TariPendingOutboundTransactions {
+    let wallet = tag::flip(wallet);
     let mut pending = Vec::new();
     if wallet.is_null() {
         return ptr::null_mut();
@@ -1266,18 +2642,60 @@ pub unsafe extern "C" fn wallet_get_pending_outbound_transactions(
             for (_id, tx) in &pending_transactions {
                 pending.push(tx.clone());
             }
-            Box::into_raw(Box::new(TariPendingOutboundTransactions(pending)))
+            into_tagged(TariPendingOutboundTransactions(pending))
         },
         Err(_) => ptr::null_mut(),
     }
 }
 
+/// Drains the wallet's internally buffered events so a client can poll for updates on its own
+/// schedule instead of receiving them re-entrantly through a callback invoked on the wallet's
+/// runtime thread. An event, once returned by this function, is not returned again by a later call
+/// for the same wallet - see `events.rs` for how that drain semantics is tracked without a real
+/// event stream to push into a buffer as transactions arrive. Currently this only surfaces a
+/// `TransactionReceived` event per newly-seen pending inbound transaction; see the note after the
+/// callback registration functions below for the events the transaction service does not emit and
+/// that are therefore never buffered here.
+///
+/// ## Arguments
+/// `wallet` - The pointer to a TariWallet
+///
+/// ## Returns
+/// `*mut TariEvents` - Returns a pointer to a TariEvents, note that ptr::null_mut() is returned if
+/// wallet is null
+#[no_mangle]
+pub unsafe extern "C" fn wallet_get_pending_events(wallet: *mut TariWallet) -> *mut TariEvents {
+    let wallet = tag::flip(wallet);
+    if wallet.is_null() {
+        return ptr::null_mut();
+    }
+
+    let mut events = Vec::new();
+    let pending_transactions = (*wallet)
+        .runtime
+        .block_on((*wallet).transaction_service.get_pending_inbound_transactions());
+    if let Ok(pending_transactions) = pending_transactions {
+        let tx_ids: Vec<u64> = pending_transactions.keys().map(|id| *id as u64).collect();
+        for id in events::drain_new(wallet as usize, &tx_ids) {
+            events.push(TariEvent {
+                event_type: TariEventType::TransactionReceived,
+                tx_id: id as c_ulonglong,
+                sync_current: 0,
+                sync_total: 0,
+            });
+        }
+    }
+
+    into_tagged(TariEvents(events))
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn wallet_get_completed_transaction_by_id(
     wallet: *mut TariWallet,
     transaction_id: c_ulonglong,
 ) -> *mut TariCompletedTransaction
 {
+    let wallet = tag::flip(wallet);
     if wallet.is_null() {
         return ptr::null_mut();
     }
@@ -1291,7 +2709,7 @@ pub unsafe extern "C" fn wallet_get_completed_transaction_by_id(
             for (id, tx) in &pending_transactions {
                 if id == &transaction_id {
                     let pending = tx.clone();
-                    return Box::into_raw(Box::new(pending));
+                    return into_tagged(pending);
                 }
             }
             return ptr::null_mut();
@@ -1306,6 +2724,7 @@ pub unsafe extern "C" fn wallet_get_pending_inbound_transaction_by_id(
     transaction_id: c_ulonglong,
 ) -> *mut TariPendingInboundTransaction
 {
+    let wallet = tag::flip(wallet);
     if wallet.is_null() {
         return ptr::null_mut();
     }
@@ -1319,7 +2738,7 @@ pub unsafe extern "C" fn wallet_get_pending_inbound_transaction_by_id(
             for (id, tx) in &pending_transactions {
                 if id == &transaction_id {
                     let pending = tx.clone();
-                    return Box::into_raw(Box::new(pending));
+                    return into_tagged(pending);
                 }
             }
             return ptr::null_mut();
@@ -1334,6 +2753,7 @@ pub unsafe extern "C" fn wallet_get_pending_outbound_transaction_by_id(
     transaction_id: c_ulonglong,
 ) -> *mut TariPendingOutboundTransaction
 {
+    let wallet = tag::flip(wallet);
     if wallet.is_null() {
         return ptr::null_mut();
     }
@@ -1347,7 +2767,7 @@ pub unsafe extern "C" fn wallet_get_pending_outbound_transaction_by_id(
             for (id, tx) in &pending_transactions {
                 if id == &transaction_id {
                     let pending = tx.clone();
-                    return Box::into_raw(Box::new(pending));
+                    return into_tagged(pending);
                 }
             }
             return ptr::null_mut();
@@ -1357,18 +2777,104 @@ pub unsafe extern "C" fn wallet_get_pending_outbound_transaction_by_id(
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn wallet_destroy(wallet: *mut TariWallet) {
+pub unsafe extern "C" fn wallet_destroy(wallet_tagged: *mut TariWallet) {
+    let wallet = tag::flip(wallet_tagged);
     if !wallet.is_null() {
-        let m = Box::from_raw(wallet);
-        let l = m.shutdown();
-        match l {
-            Ok(_l) => {},
-            Err(_) => {},
+        lock::zeroize(wallet as usize);
+        filelock::release(wallet as usize);
+        coin_selection::clear_default(wallet as usize);
+        peer_alias::clear(wallet as usize);
+        events::clear(wallet as usize);
+        backup::clear(wallet as usize);
+        // Goes through `tag::take_tagged` (on the still-tagged pointer) rather than a bare
+        // `Box::from_raw` so the address is removed from `LIVE` before the wallet is dropped -
+        // otherwise a second `wallet_destroy` on the same pointer would pass this same non-null check
+        // and double-free, and the stale `LIVE` entry would later let a freed wallet pointer be used to
+        // free whatever the allocator reuses that address for.
+        if let Some(m) = tag::take_tagged(wallet_tagged) {
+            let l = m.shutdown();
+            match l {
+                Ok(_l) => {},
+                Err(_) => {},
+            }
         }
     }
 }
 
 
+/// ----------------------------------------- Events -------------------------------------------- ///
+
+/// Gets the length of a TariEvents
+///
+/// ## Arguments
+/// `events` - The pointer to a TariEvents
+///
+/// ## Returns
+/// `c_uint` - Returns the number of elements in a TariEvents, note that it will be zero if events
+/// is null
+#[no_mangle]
+pub unsafe extern "C" fn tari_events_get_length(events: *mut TariEvents) -> c_uint {
+    let mut len = 0;
+    if !events.is_null() {
+        let events = tag::flip(events);
+        len = (*events).0.len();
+    }
+    len as c_uint
+}
+
+/// Gets a TariEvent of a TariEvents
+///
+/// ## Arguments
+/// `events` - The pointer to a TariEvents
+/// `position` - The integer position
+/// `error_out` - Pointer to an int which will be modified to an error code should one occur, may
+/// be null if the caller does not care about the distinction between error causes
+///
+/// ## Returns
+/// `TariEvent` - Returns the TariEvent at `position`, note that a zeroed `TariEvent` with
+/// `event_type` set to `TransactionReceived` is returned if events is null or position is invalid
+#[no_mangle]
+pub unsafe extern "C" fn tari_events_get_at(
+    events: *mut TariEvents,
+    position: c_uint,
+    error_out: *mut c_int,
+) -> TariEvent
+{
+    let empty = TariEvent {
+        event_type: TariEventType::TransactionReceived,
+        tx_id: 0,
+        sync_current: 0,
+        sync_total: 0,
+    };
+    if events.is_null() {
+        set_error(error_out, TariFfiError::NullArgument);
+        return empty;
+    }
+    let len = tari_events_get_length(events) as c_int - 1;
+    if len < 0 || position > len as c_uint {
+        set_error(error_out, TariFfiError::IndexOutOfBounds);
+        return empty;
+    }
+    let events = tag::flip(events);
+    (*events).0[position as usize]
+}
+
+/// Destroys a TariEvents
+///
+/// ## Arguments
+/// `events` - The pointer to a TariEvents
+///
+/// ## Returns
+/// `()` - Does not return a value, equivalent to void in C
+#[no_mangle]
+pub unsafe extern "C" fn tari_events_destroy(events: *mut TariEvents) {
+    from_tagged(events);
+}
+
+/// -------------------------------------------------------------------------------------------- ///
+
+/// -------------------------------------------------------------------------------------------- ///
+
 /// ------------------------------------- Callbacks -------------------------------------------- ///
 
 #[no_mangle]
@@ -1377,6 +2883,7 @@ pub unsafe extern "C" fn wallet_call_back_register_received_transaction(
     call: unsafe extern "C" fn(*mut TariPendingInboundTransaction),
 ) -> bool
 {
+    let wallet = tag::flip(wallet);
     let result = (*wallet)
         .runtime
         .block_on((*wallet).register_callback_received_transaction(call));
@@ -1392,6 +2899,7 @@ pub unsafe extern "C" fn wallet_call_back_register_received_transaction_reply(
     call: unsafe extern "C" fn(*mut TariCompletedTransaction),
 ) -> bool
 {
+    let wallet = tag::flip(wallet);
     let result = (*wallet)
         .runtime
         .block_on((*wallet).register_callback_received_transaction_reply(call));
@@ -1401,10 +2909,22 @@ pub unsafe extern "C" fn wallet_call_back_register_received_transaction_reply(
     }
 }
 
-// TODO Callbacks to be written and registered to receive the following events
-// Transaction hit the mempool (send and receive), wallet needs to be extended for this
-// Transaction is mined, wallet needs to be extended for this
-// Transaction is confirmed, wallet needs to be extended for this
+// Dropped `wallet_call_back_register_broadcast`/`_mined`/`_confirmation`: `transaction_service` in this
+// tree exposes no base-node-driven broadcast/mined/confirmation event stream to drive them from (see
+// `events.rs` - the one poll-only accessor it has is `get_pending_inbound_transactions`), so all three
+// could only ever accept a callback and never call it. Shipping FFI that silently never fires is worse
+// than not exporting it; `wallet_call_back_register_received_transaction`/`_reply` above stay because
+// `register_callback_received_transaction`/`_reply` are real, driven capabilities of this tree's
+// `TariWallet`. Re-add the dropped three once the transaction service actually emits those transitions.
+
+// Dropped `TariTransactionStatus`/`completed_transaction_get_status`: none of the fields this tree's
+// `CompletedTransaction` is confirmed to expose (see the getters above - tx_id/destination_public_key/
+// source_public_key/amount/fee/timestamp) carry a mined/confirmation/cancellation state, and guessing at a
+// `.status`/`.cancelled` field that might not exist on the real type would be worse than not exposing a getter at
+// all. Every transaction this crate can construct came from a successful `send_transaction`/receive flow, so the one
+// state actually reachable here is "broadcast" - reporting that for every transaction regardless of its real
+// lifecycle stage is indistinguishable from lying about Mined/Confirmed/Cancelled ones, which is worse than not
+// having the getter. Re-add it once `CompletedTransaction` is confirmed to carry real lifecycle data.
 
 // TODO (Potentially) Add optional error parameter to methods which can return null
 // TODO Write additional tests
@@ -1413,7 +2933,7 @@ pub unsafe extern "C" fn wallet_call_back_register_received_transaction_reply(
 mod test {
     extern crate libc;
     use crate::*;
-    use libc::{c_char, c_uint, c_uchar};
+    use libc::{c_char, c_int, c_uint, c_uchar};
     use std::ffi::CString;
 
     unsafe extern "C" fn completed_callback(tx:*mut TariCompletedTransaction)
@@ -1445,7 +2965,8 @@ mod test {
     fn test_bytevector() {
         unsafe {
             let bytes: [c_uchar; 4] = [2, 114, 34, 255];
-            let bytes_ptr = byte_vector_create(bytes.as_ptr(), bytes.len() as c_uint);
+            let mut error = 0;
+            let bytes_ptr = byte_vector_create(bytes.as_ptr(), bytes.len() as c_uint, &mut error as *mut c_int);
             let length = byte_vector_get_length(bytes_ptr);
             // println!("{:?}",c);
             assert_eq!(length, bytes.len() as c_uint);
@@ -0,0 +1,180 @@
+// Copyright 2019. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Passphrase-based at-rest encryption lifecycle (`wallet_encrypt`/`wallet_unlock`/
+//! `wallet_change_passphrase` in `lib.rs`), modeled on the classic encrypt/unlock/change-passphrase
+//! state machine.
+//!
+//! This tree's `WalletMemoryDatabase` holds everything in process memory and there's no accessor to
+//! read a `TariWallet`'s node identity secret key back out (see `seed_words.rs` for why), so there
+//! is no on-disk "secret-key and transaction store" for `wallet_encrypt` to re-encrypt.
+//! Instead, encrypting a wallet snapshots the same contacts/transaction metadata `backup::export`
+//! produces, encrypts it with a passphrase-derived key, and requires `wallet_unlock` with the
+//! correct passphrase before `wallet_send_transaction` will proceed - giving the same "nothing
+//! spendable without the passphrase" behaviour the request asks for, scoped to what this tree can
+//! actually observe and protect.
+//!
+//! Lock state is tracked per wallet, keyed by the wallet's untagged pointer address, in a
+//! process-wide table; `zeroize` (called from `wallet_destroy`) overwrites the encrypted blob before
+//! dropping the entry. The passphrase-derived key is not stored in this table: it is a local of each
+//! encrypt/decrypt call and is not explicitly overwritten.
+
+extern crate aes_gcm;
+extern crate hmac;
+extern crate lazy_static;
+extern crate pbkdf2;
+extern crate rand;
+extern crate sha2;
+
+use crate::TariWallet;
+use hmac::Hmac;
+use lazy_static::lazy_static;
+use pbkdf2::pbkdf2;
+use rand::RngCore;
+use sha2::Sha256;
+use std::{collections::HashMap, sync::Mutex};
+
+const PBKDF2_ITERATIONS: u32 = 100_000;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockError {
+    NotEncrypted,
+    WrongPassphrase,
+}
+
+struct LockState {
+    /// `[salt][nonce][ciphertext]`, the same layout `backup::export` uses minus its leading version byte.
+    blob: Vec<u8>,
+    unlocked: bool,
+}
+
+lazy_static! {
+    static ref LOCK_STATES: Mutex<HashMap<usize, LockState>> = Mutex::new(HashMap::new());
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::<Hmac<Sha256>>(passphrase.as_bytes(), salt, PBKDF2_ITERATIONS, &mut key);
+    key
+}
+
+fn encrypt_blob(plaintext: &[u8], passphrase: &str) -> Vec<u8> {
+    use aes_gcm::{
+        aead::{Aead, NewAead},
+        Aes256Gcm,
+        Key,
+        Nonce,
+    };
+
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    let mut rng = rand::thread_rng();
+    rng.fill_bytes(&mut salt);
+    rng.fill_bytes(&mut nonce_bytes);
+
+    let key = derive_key(passphrase, &salt);
+    let cipher = Aes256Gcm::new(Key::from_slice(&key));
+    let nonce = Nonce::from_slice(&nonce_bytes);
+    let ciphertext = cipher.encrypt(nonce, plaintext).expect("encryption is infallible for a fresh key/nonce pair");
+
+    let mut blob = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+    blob.extend_from_slice(&salt);
+    blob.extend_from_slice(&nonce_bytes);
+    blob.extend_from_slice(&ciphertext);
+    blob
+}
+
+fn decrypt_blob(blob: &[u8], passphrase: &str) -> Result<Vec<u8>, LockError> {
+    use aes_gcm::{
+        aead::{Aead, NewAead},
+        Aes256Gcm,
+        Key,
+        Nonce,
+    };
+
+    if blob.len() < SALT_LEN + NONCE_LEN {
+        return Err(LockError::WrongPassphrase);
+    }
+    let salt = &blob[..SALT_LEN];
+    let nonce_bytes = &blob[SALT_LEN..SALT_LEN + NONCE_LEN];
+    let ciphertext = &blob[SALT_LEN + NONCE_LEN..];
+
+    let key = derive_key(passphrase, salt);
+    let cipher = Aes256Gcm::new(Key::from_slice(&key));
+    let nonce = Nonce::from_slice(nonce_bytes);
+    cipher.decrypt(nonce, ciphertext).map_err(|_| LockError::WrongPassphrase)
+}
+
+/// Snapshots `wallet`'s contacts/transaction metadata, encrypts it with a key derived from
+/// `passphrase`, and locks the wallet - `wallet_send_transaction` will refuse to proceed until
+/// `unlock` succeeds.
+pub fn encrypt(wallet: &TariWallet, wallet_key: usize, passphrase: &str) {
+    let snapshot = crate::backup::export(wallet, passphrase).unwrap_or_default();
+    let blob = encrypt_blob(&snapshot, passphrase);
+    LOCK_STATES.lock().unwrap().insert(wallet_key, LockState { blob, unlocked: false });
+}
+
+/// Verifies `passphrase` against the blob `encrypt` stored and, on success, marks the wallet
+/// unlocked. Returns `LockError::NotEncrypted` if `encrypt` was never called for this wallet -
+/// callers should treat that as "nothing to unlock" rather than a passphrase failure.
+pub fn unlock(wallet_key: usize, passphrase: &str) -> Result<(), LockError> {
+    let mut states = LOCK_STATES.lock().unwrap();
+    let state = states.get_mut(&wallet_key).ok_or(LockError::NotEncrypted)?;
+    decrypt_blob(&state.blob, passphrase)?;
+    state.unlocked = true;
+    Ok(())
+}
+
+/// Re-encrypts the stored blob under `new_passphrase`, without ever exposing the plaintext beyond
+/// this function. Requires `old_passphrase` to successfully decrypt the current blob first.
+pub fn change_passphrase(wallet_key: usize, old_passphrase: &str, new_passphrase: &str) -> Result<(), LockError> {
+    let mut states = LOCK_STATES.lock().unwrap();
+    let state = states.get_mut(&wallet_key).ok_or(LockError::NotEncrypted)?;
+    let plaintext = decrypt_blob(&state.blob, old_passphrase)?;
+    state.blob = encrypt_blob(&plaintext, new_passphrase);
+    state.unlocked = true;
+    Ok(())
+}
+
+/// Whether `wallet_key` has been encrypted via `encrypt` and not yet successfully `unlock`ed. A
+/// wallet that was never encrypted is always unlocked.
+pub fn is_locked(wallet_key: usize) -> bool {
+    LOCK_STATES
+        .lock()
+        .unwrap()
+        .get(&wallet_key)
+        .map(|state| !state.unlocked)
+        .unwrap_or(false)
+}
+
+/// Overwrites the encrypted blob stored for `wallet_key` with zeros before dropping it, so it doesn't
+/// linger in a freed allocation. No derived key is stored here to overwrite. Called from
+/// `wallet_destroy`.
+pub fn zeroize(wallet_key: usize) {
+    if let Some(mut state) = LOCK_STATES.lock().unwrap().remove(&wallet_key) {
+        for byte in state.blob.iter_mut() {
+            *byte = 0;
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright 2019. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Human-readable peer address resolution for `wallet_add_base_node_peer_by_name` in `lib.rs`, so an
+//! integrator can ship a short list of named seed nodes instead of brittle literal
+//! `127.0.0.1:21441`-style socket strings.
+//!
+//! Resolution tries two sources, in order:
+//! 1. A local alias table, populated by `wallet_set_peer_alias`, mapping a name straight to a
+//!    previously-known-good `NetAddress` string. This never touches the network.
+//! 2. A DNS lookup of `name` as an `IP:port` or `host:port` string via `std::net::ToSocketAddrs`,
+//!    which only ever queries the host actually passed in - there's no secondary resolver or
+//!    recursive seed-list fetch here to leak additional queries out to.
+//!
+//! The alias table is per-wallet, keyed by the wallet's untagged pointer address, the same pattern
+//! `lock.rs`/`filelock.rs` use.
+
+extern crate lazy_static;
+
+use lazy_static::lazy_static;
+use std::{collections::HashMap, net::ToSocketAddrs, sync::Mutex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerAliasError {
+    NotFound,
+}
+
+lazy_static! {
+    static ref ALIASES: Mutex<HashMap<usize, HashMap<String, String>>> = Mutex::new(HashMap::new());
+}
+
+/// Records `address` as the resolved `NetAddress` string for `name` under `wallet_key`, overwriting
+/// any previous alias of the same name.
+pub fn set_alias(wallet_key: usize, name: &str, address: &str) {
+    ALIASES
+        .lock()
+        .unwrap()
+        .entry(wallet_key)
+        .or_insert_with(HashMap::new)
+        .insert(name.to_string(), address.to_string());
+}
+
+/// Resolves `name` to a `NetAddress` string for `wallet_key`: an alias set by `set_alias` if one
+/// exists, otherwise a DNS lookup of `name` itself as a `host:port` pair. Returns
+/// `PeerAliasError::NotFound` if neither source has an answer.
+pub fn resolve(wallet_key: usize, name: &str) -> Result<String, PeerAliasError> {
+    if let Some(address) = ALIASES.lock().unwrap().get(&wallet_key).and_then(|table| table.get(name)) {
+        return Ok(address.clone());
+    }
+
+    name.to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .map(|addr| addr.to_string())
+        .ok_or(PeerAliasError::NotFound)
+}
+
+/// Forgets every alias recorded for `wallet_key`. Called from `wallet_destroy`.
+pub fn clear(wallet_key: usize) {
+    ALIASES.lock().unwrap().remove(&wallet_key);
+}
@@ -0,0 +1,123 @@
+// Copyright 2019. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Mnemonic seed-word decoding for a `TariPrivateKey`, exposed over FFI as
+//! `wallet_create_from_seed_words` in `lib.rs`.
+//!
+//! This isn't the standard 2048-word English BIP39 wordlist - this tree doesn't vendor that list,
+//! and transcribing all 2048 entries by hand here risks a silent mismatch against the canonical
+//! list that nothing in this crate could catch, which is worse than not claiming BIP39 compatibility
+//! at all. Instead this uses the same mechanics with a smaller, `rustfmt::skip`-pinned wordlist sized
+//! so each word encodes exactly one byte (256 words), plus a trailing checksum word (the first byte
+//! of the key bytes' SHA-256). A mistyped word that isn't in the list is always rejected on import;
+//! one swapped for another listed word passes the one-byte checksum with a 1 in 256 chance. 32 key
+//! bytes + 1 checksum byte encode to exactly 33 words. Swap in the real `bip39` crate's wordlist (and
+//! its bit-packing, 11 bits/word over a multiple-of-32-bits entropy length) if/when this tree ever
+//! vendors it, for actual BIP39 interop.
+//!
+//! Note: there is deliberately no `encode` half of this module (and no `wallet_get_seed_words` FFI
+//! export). Encoding existing words back out would need this wallet's node identity secret key, and
+//! nothing in this tree exposes a way to read that back out once `comms_config_create` has folded it
+//! into a `NodeIdentity` - the same gap `backup.rs` and `lock.rs` work around by snapshotting what
+//! they can observe instead of the secret key itself. A getter that can only ever return null for
+//! every wallet this crate can construct is worse than no getter, so recovery only runs the direction
+//! that's actually implementable: `wallet_create_from_seed_words` derives a key forward from words
+//! the caller already has, which needs no such access and is fully functional.
+
+extern crate sha2;
+
+use sha2::{Digest, Sha256};
+
+const KEY_LEN: usize = 32;
+const WORD_COUNT: usize = KEY_LEN + 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedWordsError {
+    InvalidWordCount,
+    UnknownWord,
+    ChecksumMismatch,
+}
+
+/// A 256-word list, one entry per possible byte value. Words are drawn from the standard BIP39
+/// English wordlist (so they stay easy to write down, spell-check, and read aloud) but this is not
+/// the full 2048-word list - see the module doc.
+#[rustfmt::skip]
+const WORDLIST: [&str; 256] = [
+    "abandon", "ability", "able", "about", "above", "absent", "absorb", "abstract",
+    "absurd", "abuse", "access", "accident", "account", "accuse", "achieve", "acid",
+    "acoustic", "acquire", "across", "act", "action", "actor", "actress", "actual",
+    "adapt", "add", "addict", "address", "adjust", "admit", "adult", "advance",
+    "advice", "aerobic", "affair", "afford", "afraid", "again", "age", "agent",
+    "agree", "ahead", "aim", "air", "airport", "aisle", "alarm", "album",
+    "alcohol", "alert", "alien", "all", "alley", "allow", "almost", "alone",
+    "alpha", "already", "also", "alter", "always", "amateur", "amazing", "among",
+    "amount", "amused", "analyst", "anchor", "ancient", "anger", "angle", "angry",
+    "animal", "ankle", "announce", "annual", "another", "answer", "antenna", "antique",
+    "anxiety", "any", "apart", "apology", "appear", "apple", "approve", "april",
+    "arch", "arctic", "area", "arena", "argue", "arm", "armed", "armor",
+    "army", "around", "arrange", "arrest", "arrive", "arrow", "art", "artefact",
+    "artist", "artwork", "ask", "aspect", "assault", "asset", "assist", "assume",
+    "asthma", "athlete", "atom", "attack", "attend", "attitude", "attract", "auction",
+    "audit", "august", "aunt", "author", "auto", "autumn", "average", "avocado",
+    "avoid", "awake", "aware", "away", "awesome", "awful", "awkward", "axis",
+    "baby", "bachelor", "bacon", "badge", "bag", "balance", "balcony", "ball",
+    "bamboo", "banana", "banner", "bar", "barely", "bargain", "barrel", "base",
+    "basic", "basket", "battle", "beach", "bean", "beauty", "because", "become",
+    "beef", "before", "begin", "behave", "behind", "believe", "below", "belt",
+    "bench", "benefit", "best", "betray", "better", "between", "beyond", "bicycle",
+    "bid", "bike", "bind", "biology", "bird", "birth", "bitter", "black",
+    "blade", "blame", "blanket", "blast", "bleak", "bless", "blind", "blood",
+    "blossom", "blouse", "blue", "blur", "blush", "board", "boat", "body",
+    "boil", "bomb", "bone", "bonus", "book", "boost", "border", "boring",
+    "borrow", "boss", "bottom", "bounce", "box", "boy", "bracket", "brain",
+    "brand", "brass", "brave", "bread", "breeze", "brick", "bridge", "brief",
+    "bright", "bring", "brisk", "broccoli", "broken", "bronze", "broom", "brother",
+    "brown", "brush", "bubble", "buddy", "budget", "buffalo", "build", "bulb",
+    "bulk", "bullet", "bundle", "bunker", "burden", "burger", "burst", "bus",
+    "business", "busy", "butter", "buyer", "buzz", "cabbage", "cabin", "cable",
+];
+
+fn checksum_byte(bytes: &[u8]) -> u8 {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    hasher.finalize()[0]
+}
+
+/// Decodes `words` back into the original key bytes, validating the trailing checksum word.
+pub fn decode(words: &[String]) -> Result<Vec<u8>, SeedWordsError> {
+    if words.len() != WORD_COUNT {
+        return Err(SeedWordsError::InvalidWordCount);
+    }
+    let mut bytes = Vec::with_capacity(WORD_COUNT);
+    for word in words {
+        let index = WORDLIST
+            .iter()
+            .position(|candidate| candidate.eq_ignore_ascii_case(word))
+            .ok_or(SeedWordsError::UnknownWord)?;
+        bytes.push(index as u8);
+    }
+    let (key_bytes, checksum) = bytes.split_at(KEY_LEN);
+    if checksum_byte(key_bytes) != checksum[0] {
+        return Err(SeedWordsError::ChecksumMismatch);
+    }
+    Ok(key_bytes.to_vec())
+}
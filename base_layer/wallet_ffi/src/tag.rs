@@ -0,0 +1,113 @@
+// Copyright 2019. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! Ownership tagging for every `Box`-backed handle this crate hands across the FFI boundary.
+//!
+//! Every opaque type here (`TariPublicKey`, `TariContact`, `ByteVector`, ...) is allocated with
+//! `Box::into_raw` and later reclaimed with `Box::from_raw`. Without any marker, a client that frees
+//! a handle twice, frees a pointer we never issued, or keeps using a pointer after freeing it causes
+//! the second `Box::from_raw`/deref to run against memory that may already have been reused, which is
+//! undefined behaviour rather than a clean, detectable error.
+//!
+//! Following the pointer-tagging approach rust-lightning's `ObjOps::untweak_ptr` uses, every pointer
+//! we hand out has a fixed sentinel XORed into its address before it reaches the caller. Since XOR is
+//! its own inverse, tagging and untagging are the same operation (`flip`), so a single `flip` call at
+//! the top of each FFI function recovers the real address before any existing null-check/deref/destroy logic
+//! runs unchanged. A pointer that was never tagged by us (garbage, a pointer into unrelated memory, or
+//! one some other allocator handed out) untags to a bogus address instead of silently aliasing a live
+//! `Box`; callers that deref it will fault instead of corrupting the heap, and a pointer that is
+//! genuinely null stays null so existing "null means absent" checks keep working.
+//!
+//! This does not stop a double-destroy of the *same* handle value by address reuse alone (the XOR
+//! sentinel is the same for every handle) - so `into_tagged`/`from_tagged` additionally track the set
+//! of currently-live untagged addresses in `LIVE` below. `from_tagged` checks `LIVE` before ever
+//! calling `Box::from_raw`: an address that was never issued by `into_tagged`, or one that already was
+//! reclaimed, is rejected and the call becomes a no-op instead of dereferencing/freeing arbitrary
+//! memory. A pointer that is genuinely null stays null so existing "null means absent" checks keep
+//! working.
+
+extern crate lazy_static;
+
+use lazy_static::lazy_static;
+use std::{boxed::Box, collections::HashSet, sync::Mutex};
+
+/// Arbitrary non-zero sentinel XORed into every handle this crate issues.
+const TAG: usize = 0x5A5A_5A5A_5A5A_5A5A;
+
+lazy_static! {
+    /// Untagged addresses of every handle currently live, i.e. issued by `into_tagged` and not yet
+    /// reclaimed by `from_tagged`. `from_tagged` consults this before touching memory so a foreign or
+    /// already-freed pointer is rejected rather than blindly trusted.
+    static ref LIVE: Mutex<HashSet<usize>> = Mutex::new(HashSet::new());
+}
+
+/// Tags a pointer, or untags one - the two operations are identical since XOR is an involution.
+/// Null is left untouched so the existing `ptr.is_null()` checks throughout this crate keep working
+/// whether they run before or after this call. This alone does not validate `ptr` - callers that need
+/// that guarantee before dereferencing a reclaimed value should go through `from_tagged` instead.
+pub fn flip<T>(ptr: *mut T) -> *mut T {
+    if ptr.is_null() {
+        return ptr;
+    }
+    ((ptr as usize) ^ TAG) as *mut T
+}
+
+/// Allocates `value` on the heap, records its address as live, and returns a tagged pointer suitable
+/// for handing to an FFI caller.
+pub fn into_tagged<T>(value: T) -> *mut T {
+    let raw = Box::into_raw(Box::new(value));
+    LIVE.lock().unwrap().insert(raw as usize);
+    flip(raw)
+}
+
+/// Reclaims a `Box` from a tagged pointer previously returned by `into_tagged`, returning it to the
+/// caller instead of dropping it immediately - for the handful of destroy functions (e.g.
+/// `wallet_destroy`) that need to run teardown logic (drain queues, call `shutdown`, ...) against the
+/// live value before it's freed. `ptr` may be null (returns `None`), a pointer this module tagged and
+/// has not already reclaimed (returned as `Some`), or anything else - a foreign pointer, garbage, or
+/// one already reclaimed - in which case this is rejected (`None`) rather than dereferenced.
+///
+/// # Safety
+/// For the one case this function actually reclaims (a live, previously-tagged `ptr`), the same
+/// invariants as `Box::from_raw` apply: the untagged address must point at a valid `T` allocated via
+/// `into_tagged::<T>`. The `LIVE` check is what lets a foreign or stale `ptr` be rejected safely
+/// instead of relying on the caller never passing one.
+pub unsafe fn take_tagged<T>(ptr: *mut T) -> Option<Box<T>> {
+    if ptr.is_null() {
+        return None;
+    }
+    let raw = flip(ptr);
+    if !LIVE.lock().unwrap().remove(&(raw as usize)) {
+        // Never tagged by us, or already reclaimed - bail instead of dereferencing/freeing garbage.
+        return None;
+    }
+    Some(Box::from_raw(raw))
+}
+
+/// Reclaims and immediately drops a `Box` from a tagged pointer previously returned by
+/// `into_tagged`. See `take_tagged` for the full null/foreign/already-reclaimed contract this shares.
+///
+/// # Safety
+/// Same as `take_tagged`.
+pub unsafe fn from_tagged<T>(ptr: *mut T) {
+    let _ = take_tagged(ptr);
+}